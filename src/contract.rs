@@ -1,33 +1,69 @@
-use apollo_cw_asset::{Asset, AssetInfo, AssetInfoUnchecked};
+use apollo_cw_asset::{
+    Asset, AssetInfo, AssetInfoKey, AssetInfoUnchecked, AssetList, AssetListUnchecked,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, Event, MessageInfo,
-    Order, Response, StdError, StdResult, Uint128,
+    from_json, to_json_binary, to_json_string, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, Event, MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, Storage,
+    SubMsg, SubMsgResult, Uint128,
 };
 use cw2::set_contract_version;
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20ReceiveMsg, Expiration};
+use cw_dex::Pool;
+use cw_storage_plus::{Bound, Map};
+use cw_utils::parse_execute_response_data;
 
+use crate::discovery::{
+    find_routes, MAX_DISCOVERY_HOPS, MAX_REGISTERED_POOLS, MAX_SIMULATED_ROUTES,
+};
 use crate::error::ContractError;
-use crate::helpers::receive_asset;
+use crate::helpers::{receive_asset, receive_assets};
 use crate::msg::{
-    BestPathForPairResponse, CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg,
-    QueryMsg,
+    AdminResponse, BestPathForPairResponse, BestSplitForPairResponse, CallbackMsg, Cw20HookMsg,
+    DiscoveredPath, ExecuteMsg, FailedPath, InstantiateMsg, MigrateMsg, PathSimulation,
+    PauseInfoResponse, QueryMsg, SimulateReverseSwapResponse, SimulateSwapResponse, SwapResponse,
 };
 use crate::operations::{SwapOperation, SwapOperationsList, SwapOperationsListUnchecked};
-use crate::state::{ADMIN, PATHS};
+use crate::state::{
+    blocked_pool_key, is_asset_allowed, is_pool_blocked, Config, HopPenalty, PendingSwap,
+    RegisteredPool, ADMIN, ALLOWED_ASSETS, BLOCKED_POOLS, BPS_DENOMINATOR, CONFIG, DENIED_ASSETS,
+    GUARDIAN, LAST_PATH_IDS, LAST_POOL_ID, MAX_FEE_BPS, OFFER_ASSETS_BY_ASK, PATHS, PATH_MANAGERS,
+    PAUSED, PENDING_ADMIN, PENDING_SWAP, POOLS,
+};
 
 const CONTRACT_NAME: &str = "crates.io:cw-dex-router";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const DEFAULT_SPLIT_GRANULARITY: u32 = 10;
+const MAX_SPLIT_GRANULARITY: u32 = 50;
+
+/// The reply id of the final callback of a swap.
+const SWAP_RESULT_REPLY_ID: u64 = 1;
+/// The reply id of the swap of a single operation.
+const SWAP_OPERATION_REPLY_ID: u64 = 2;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        fee_bps: msg.fee_bps,
+        fee_recipient: deps.api.addr_validate(&msg.fee_recipient)?,
+        default_hop_penalty: None,
+        allowlist_enabled: false,
+    };
+    validate_config(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+
     ADMIN.set(deps, Some(info.sender))?;
 
     Ok(Response::default())
@@ -40,14 +76,31 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    // Only swaps are paused, so that the admin can still fix the router
+    if matches!(
+        msg,
+        ExecuteMsg::Receive(_)
+            | ExecuteMsg::ExecuteSwapOperations { .. }
+            | ExecuteMsg::SwapBestPath { .. }
+            | ExecuteMsg::ExecuteSwapOperationsExactOut { .. }
+            | ExecuteMsg::ExecuteSplitSwap { .. }
+            | ExecuteMsg::BasketLiquidate { .. }
+    ) {
+        assert_not_paused(deps.as_ref())?;
+    }
+
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::ExecuteSwapOperations {
             operations,
             offer_amount,
             minimum_receive,
+            max_spread,
+            belief_price,
             to,
+            deadline,
         } => {
+            assert_deadline(&env, deadline)?;
             let operations = operations.check(deps.as_ref())?;
             execute_swap_operations(
                 deps,
@@ -57,26 +110,68 @@ pub fn execute(
                 operations,
                 offer_amount,
                 minimum_receive,
+                max_spread,
+                belief_price,
+                to,
+            )
+        }
+        ExecuteMsg::SwapBestPath {
+            offer_asset,
+            ask_asset,
+            minimum_receive,
+            to,
+            exclude_paths,
+        } => {
+            let offer_asset = offer_asset.check(deps.api)?;
+            let ask_asset = ask_asset.check(deps.api)?;
+            swap_best_path(
+                deps,
+                env,
+                Some(info.clone()),
+                info.sender,
+                offer_asset,
+                ask_asset,
+                minimum_receive,
+                to,
+                exclude_paths,
+            )
+        }
+        ExecuteMsg::ExecuteSwapOperationsExactOut {
+            operations,
+            ask_amount,
+            max_offer,
+            to,
+        } => {
+            let operations = operations.check(deps.as_ref())?;
+            execute_swap_operations_exact_out(
+                deps, env, info, operations, ask_amount, max_offer, to,
+            )
+        }
+        ExecuteMsg::ExecuteSplitSwap {
+            routes,
+            minimum_receive,
+            to,
+        } => {
+            let routes = check_split_routes(deps.as_ref(), routes)?;
+            execute_split_swap(deps, env, info, routes, minimum_receive, to)
+        }
+        ExecuteMsg::BasketLiquidate {
+            offer_assets,
+            receive_asset,
+            minimum_receive,
+            to,
+        } => {
+            let api = deps.api;
+            basket_liquidate(
+                deps,
+                env,
+                info,
+                offer_assets.check(api)?,
+                receive_asset.check(api)?,
+                minimum_receive,
                 to,
             )
         }
-        // ExecuteMsg::BasketLiquidate {
-        //     offer_assets,
-        //     receive_asset,
-        //     minimum_receive,
-        //     to,
-        // } => {
-        //     let api = deps.api;
-        //     basket_liquidate(
-        //         deps,
-        //         env,
-        //         info,
-        //         offer_assets.check(api)?,
-        //         receive_asset.check(api)?,
-        //         minimum_receive,
-        //         to,
-        //     )
-        // }
         ExecuteMsg::SetPath {
             offer_asset,
             ask_asset,
@@ -94,14 +189,90 @@ pub fn execute(
                 bidirectional,
             )
         }
+        ExecuteMsg::RemovePath {
+            offer_asset,
+            ask_asset,
+            path_id,
+            bidirectional,
+        } => {
+            let api = deps.api;
+            remove_path(
+                deps,
+                info,
+                offer_asset.check(api)?,
+                ask_asset.check(api)?,
+                path_id,
+                bidirectional,
+            )
+        }
+        ExecuteMsg::ReplacePath {
+            offer_asset,
+            ask_asset,
+            path_id,
+            path,
+            bidirectional,
+        } => {
+            let path = path.check(deps.as_ref())?;
+            let api = deps.api;
+            replace_path(
+                deps,
+                info,
+                offer_asset.check(api)?,
+                ask_asset.check(api)?,
+                path_id,
+                path,
+                bidirectional,
+            )
+        }
+        ExecuteMsg::AddPathManager { address } => add_path_manager(deps, info, address),
+        ExecuteMsg::RemovePathManager { address } => remove_path_manager(deps, info, address),
+        ExecuteMsg::RegisterPool { pool } => register_pool(deps, info, pool),
+        ExecuteMsg::DeregisterPool { pool_id } => deregister_pool(deps, info, pool_id),
+        ExecuteMsg::ProposeNewAdmin { new_admin } => propose_new_admin(deps, info, new_admin),
+        ExecuteMsg::AcceptAdmin {} => accept_admin(deps, info),
+        ExecuteMsg::ClearProposal {} => clear_proposal(deps, info),
+        ExecuteMsg::RenounceAdmin {} => renounce_admin(deps, info),
+        ExecuteMsg::UpdateConfig {
+            fee_bps,
+            fee_recipient,
+            default_hop_penalty,
+            clear_default_hop_penalty,
+            allowlist_enabled,
+        } => update_config(
+            deps,
+            info,
+            fee_bps,
+            fee_recipient,
+            default_hop_penalty,
+            clear_default_hop_penalty,
+            allowlist_enabled,
+        ),
+        ExecuteMsg::SetGuardian { guardian } => set_guardian(deps, info, guardian),
+        ExecuteMsg::Pause {} => set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => set_paused(deps, info, false),
+        ExecuteMsg::BlockPool { pool } => block_pool(deps, info, pool),
+        ExecuteMsg::UnblockPool { pool } => unblock_pool(deps, info, pool),
+        ExecuteMsg::UpdateAllowedAssets { add, remove } => {
+            update_asset_list(deps, info, ALLOWED_ASSETS, "allowed", add, remove)
+        }
+        ExecuteMsg::UpdateDeniedAssets { add, remove } => {
+            update_asset_list(deps, info, DENIED_ASSETS, "denied", add, remove)
+        }
         ExecuteMsg::Callback(msg) => {
             if info.sender != env.contract.address {
                 return Err(ContractError::Unauthorized);
             }
             match msg {
-                CallbackMsg::ExecuteSwapOperation { operation, to } => {
-                    execute_swap_operation(deps, env, operation, to)
-                }
+                CallbackMsg::ExecuteSwapOperations {
+                    operations,
+                    to,
+                    offer_amount,
+                } => execute_swap_operation(deps, env, operations, to, offer_amount, 0),
+                CallbackMsg::CollectFee {
+                    asset_info,
+                    prev_balance,
+                    recipient,
+                } => collect_fee(deps, env, asset_info, prev_balance, recipient),
                 CallbackMsg::AssertMinimumReceive {
                     asset_info,
                     prev_balance,
@@ -116,6 +287,10 @@ pub fn execute(
                     minimum_receive,
                     recipient,
                 ),
+                CallbackMsg::RefundLeftover {
+                    prev_balances,
+                    recipient,
+                } => refund_leftover(deps, env, prev_balances, recipient),
             }
         }
     }
@@ -133,18 +308,49 @@ pub fn receive_cw20(
         Cw20HookMsg::ExecuteSwapOperations {
             operations,
             minimum_receive,
+            max_spread,
+            belief_price,
             to,
+            deadline,
         } => {
+            assert_deadline(&env, deadline)?;
             let operations = operations.check(deps.as_ref())?;
-            execute_swap_operations(
+            if operations.from() != AssetInfo::Cw20(info.sender.clone()) {
+                return Err(ContractError::InvalidSwapOperations {
+                    operations: operations.into(),
+                    reason: "The offer asset must be the received Cw20 token".to_string(),
+                });
+            }
+            execute_received_swap_operations(
                 deps,
                 env,
-                info,
                 sender,
                 operations,
+                cw20_msg.amount,
+                minimum_receive,
+                max_spread,
+                belief_price,
+                to,
+            )
+        }
+        Cw20HookMsg::SwapBestPath {
+            ask_asset,
+            minimum_receive,
+            to,
+            exclude_paths,
+        } => {
+            let offer_asset = Asset::new(AssetInfo::Cw20(info.sender.clone()), cw20_msg.amount);
+            let ask_asset = ask_asset.check(deps.api)?;
+            swap_best_path(
+                deps,
+                env,
                 None,
+                sender,
+                offer_asset,
+                ask_asset,
                 minimum_receive,
                 to,
+                exclude_paths,
             )
         }
     }
@@ -159,390 +365,3511 @@ pub fn execute_swap_operations(
     operations: SwapOperationsList,
     offer_amount: Option<Uint128>,
     minimum_receive: Option<Uint128>,
+    max_spread: Option<Decimal>,
+    belief_price: Option<Decimal>,
     to: Option<String>,
 ) -> Result<Response, ContractError> {
     //Validate input or use sender address if None
-    let recipient = to.map_or(Ok(sender), |x| deps.api.addr_validate(&x))?;
+    let recipient = to.map_or(Ok(sender.clone()), |x| deps.api.addr_validate(&x))?;
 
-    let target_asset_info = operations.to();
     let offer_asset_info = operations.from();
+    assert_only_offer_funds(&info, &[offer_asset_info.clone()])?;
 
-    // 1. Validate sent asset. We only do this if the passed in optional
-    // `offer_amount` and in this case we do transfer from on it, given that
-    // the offer asset is a CW20. Otherwise we assume the caller already sent
-    // funds and in the first call of execute_swap_operation, we just use the
-    // whole contracts balance.
+    // 1. Validate sent asset. If `offer_amount` is given we do TransferFrom on
+    // it, given that the offer asset is a CW20, or assert that exactly this
+    // amount of the native token was sent. Otherwise the offer asset must be a
+    // native token and we swap the amount that was sent.
     let mut msgs: Vec<CosmosMsg> = vec![];
-    if let Some(offer_amount) = offer_amount {
-        msgs.extend(receive_asset(
-            &info,
-            &env,
-            &Asset::new(offer_asset_info.clone(), offer_amount),
-        )?);
+    let offer_amount = match offer_amount {
+        Some(offer_amount) => {
+            msgs.extend(receive_asset(
+                &info,
+                &env,
+                &Asset::new(offer_asset_info.clone(), offer_amount),
+            )?);
+            offer_amount
+        }
+        None => match &offer_asset_info {
+            AssetInfo::Native(denom) => info
+                .funds
+                .iter()
+                .find(|coin| &coin.denom == denom)
+                .map(|coin| coin.amount)
+                .ok_or(ContractError::IncorrectNativeAmountSent)?,
+            AssetInfo::Cw20(_) => return Err(ContractError::MustProvideOfferAmount),
+        },
     };
 
-    // 2. Loop and execute swap operations
-    let mut msgs: Vec<CosmosMsg> = operations.into_execute_msgs(&env, recipient.clone())?;
+    // 2. Loop and execute swap operations after the offer asset was received
+    let minimum_receive =
+        minimum_receive_with_max_spread(offer_amount, minimum_receive, max_spread, belief_price)?;
+    let (swap_msgs, result_msg) = swap_operations_msgs(
+        deps.as_ref(),
+        &env,
+        &operations,
+        offer_amount,
+        minimum_receive,
+        &recipient,
+    )?;
+    msgs.extend(swap_msgs);
 
-    // 3. Assert min receive
-    if let Some(minimum_receive) = minimum_receive {
-        let recipient_balance =
-            target_asset_info.query_balance(&deps.querier, recipient.clone())?;
-        msgs.push(
-            CallbackMsg::AssertMinimumReceive {
-                asset_info: target_asset_info,
-                prev_balance: recipient_balance,
-                token_in: Asset::new(offer_asset_info, offer_amount.unwrap_or_default()),
-                minimum_receive,
-                recipient,
-            }
-            .into_cosmos_msg(&env)?,
-        );
-    }
-    Ok(Response::new().add_messages(msgs))
+    // 3. Refund what is left of the offer and intermediate assets. A native
+    // offer asset is already held by the contract, a Cw20 is only received
+    // with the TransferFrom above.
+    let received_amount = match offer_asset_info {
+        AssetInfo::Native(_) => offer_amount,
+        AssetInfo::Cw20(_) => Uint128::zero(),
+    };
+    msgs.push(refund_leftover_msg(
+        deps.as_ref(),
+        &env,
+        operations.offer_asset_infos(),
+        &[Asset::new(offer_asset_info, received_amount)],
+        &sender,
+    )?);
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_submessage(result_msg))
 }
 
-pub fn execute_swap_operation(
+/// Executes the swap operations on `offer_amount` of the offer asset, that
+/// the contract already received through the Cw20 receive hook.
+#[allow(clippy::too_many_arguments)]
+fn execute_received_swap_operations(
     deps: DepsMut,
     env: Env,
-    operation: SwapOperation,
-    to: Addr,
+    sender: Addr,
+    operations: SwapOperationsList,
+    offer_amount: Uint128,
+    minimum_receive: Option<Uint128>,
+    max_spread: Option<Decimal>,
+    belief_price: Option<Decimal>,
+    to: Option<String>,
 ) -> Result<Response, ContractError> {
-    //We use all of the contracts balance.
-    let offer_amount = operation
-        .offer_asset_info
-        .query_balance(&deps.querier, env.contract.address.to_string())?;
-
-    if offer_amount.is_zero() {
-        return Ok(Response::default());
-    }
+    //Validate input or use sender address if None
+    let recipient = to.map_or(Ok(sender.clone()), |x| deps.api.addr_validate(&x))?;
 
-    let event = Event::new("apollo/cw-dex-router/callback_execute_swap_operation")
-        .add_attribute("operation", format!("{:?}", operation))
-        .add_attribute("offer_amount", offer_amount)
-        .add_attribute("to", to.to_string());
+    let minimum_receive =
+        minimum_receive_with_max_spread(offer_amount, minimum_receive, max_spread, belief_price)?;
+    let (mut msgs, result_msg) = swap_operations_msgs(
+        deps.as_ref(),
+        &env,
+        &operations,
+        offer_amount,
+        minimum_receive,
+        &recipient,
+    )?;
+    msgs.push(refund_leftover_msg(
+        deps.as_ref(),
+        &env,
+        operations.offer_asset_infos(),
+        &[Asset::new(operations.from(), offer_amount)],
+        &sender,
+    )?);
 
-    Ok(operation
-        .to_cosmos_response(deps.as_ref(), &env, offer_amount, None, to)?
-        .add_event(event))
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_submessage(result_msg))
 }
 
-pub fn assert_minimum_receive(
-    deps: DepsMut,
-    asset_info: AssetInfo,
-    prev_balance: Uint128,
-    token_in: Asset,
-    minimum_receive: Uint128,
-    recipient: Addr,
-) -> Result<Response, ContractError> {
-    let recipient_balance = asset_info.query_balance(&deps.querier, recipient)?;
-
-    let received_amount = recipient_balance.checked_sub(prev_balance)?;
-
-    if received_amount < minimum_receive {
-        return Err(ContractError::FailedMinimumReceive {
-            token_in,
-            wanted: Asset::new(asset_info.clone(), minimum_receive),
-            got: Asset::new(asset_info, received_amount),
-        });
+/// Asserts that the optional deadline of a swap has not passed.
+fn assert_deadline(env: &Env, deadline: Option<Expiration>) -> Result<(), ContractError> {
+    match deadline {
+        Some(deadline) if deadline.is_expired(&env.block) => {
+            Err(ContractError::DeadlineExpired { deadline })
+        }
+        _ => Ok(()),
     }
-    Ok(Response::default())
 }
 
-pub fn set_path(
-    deps: DepsMut,
-    info: MessageInfo,
-    offer_asset: AssetInfo,
-    ask_asset: AssetInfo,
-    path: SwapOperationsList,
-    bidirectional: bool,
-) -> Result<Response, ContractError> {
-    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
-
-    // Validate the path
-    if path.from() != offer_asset || path.to() != ask_asset {
-        return Err(ContractError::InvalidSwapOperations {
-            operations: path.into(),
-            reason: "The path does not match the offer and ask assets".to_string(),
-        });
+/// Returns the minimum amount to receive. If `max_spread` is given, the
+/// minimum is derived from the return expected at `belief_price`, and the
+/// higher of it and `minimum_receive` is used. `max_spread` and
+/// `belief_price` must be given together.
+fn minimum_receive_with_max_spread(
+    offer_amount: Uint128,
+    minimum_receive: Option<Uint128>,
+    max_spread: Option<Decimal>,
+    belief_price: Option<Decimal>,
+) -> Result<Option<Uint128>, ContractError> {
+    let (max_spread, belief_price) = match (max_spread, belief_price) {
+        (Some(max_spread), Some(belief_price)) => (max_spread, belief_price),
+        (None, None) => return Ok(minimum_receive),
+        _ => return Err(ContractError::MaxSpreadWithoutBeliefPrice),
+    };
+    if max_spread > Decimal::one() {
+        return Err(ContractError::InvalidMaxSpread { max_spread });
     }
 
-    // check if we have any exisiting items under the offer_asset, ask_asset pair
-    // we are looking for the highest ID so we can increment it, this should be under Order::Descending in the first item
-    let ps: Result<Vec<(u64, SwapOperationsList)>, StdError> = PATHS
-        .prefix((offer_asset.clone().into(), ask_asset.clone().into()))
-        .range(deps.storage, None, None, Order::Descending)
-        .collect();
-    let paths = ps?;
-    let last_id = paths.first().map(|(val, _)| val).unwrap_or(&0);
+    let expected_return = offer_amount
+        * belief_price
+            .inv()
+            .ok_or(ContractError::InvalidBeliefPrice { belief_price })?;
+    let spread_minimum = expected_return * (Decimal::one() - max_spread);
 
-    let new_id = last_id + 1;
-    PATHS.save(
-        deps.storage,
-        ((&offer_asset).into(), (&ask_asset).into(), new_id),
-        &path,
-    )?;
+    Ok(Some(minimum_receive.map_or(spread_minimum, |minimum| {
+        minimum.max(spread_minimum)
+    })))
+}
 
-    // reverse path and store if `bidirectional` is true
-    if bidirectional {
-        let ps: Result<Vec<(u64, SwapOperationsList)>, StdError> = PATHS
-            .prefix((ask_asset.clone().into(), offer_asset.clone().into()))
-            .range(deps.storage, None, None, Order::Descending)
-            .collect();
-        let paths = ps?;
-        let last_id = paths.first().map(|(val, _)| val).unwrap_or(&0);
-
-        let new_id = last_id + 1;
-        PATHS.save(
-            deps.storage,
-            (ask_asset.into(), offer_asset.into(), new_id),
-            &path.reverse(),
-        )?;
+/// Asserts that no funds other than the native offer assets were sent.
+fn assert_only_offer_funds(
+    info: &MessageInfo,
+    offer_asset_infos: &[AssetInfo],
+) -> Result<(), ContractError> {
+    let unexpected_funds = info
+        .funds
+        .iter()
+        .any(|coin| !offer_asset_infos.contains(&AssetInfo::Native(coin.denom.clone())));
+    if unexpected_funds {
+        return Err(ContractError::UnexpectedFunds {
+            funds: info.funds.clone(),
+            offer_asset: offer_asset_infos
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        });
     }
-
-    Ok(Response::default())
+    Ok(())
 }
 
-// pub fn basket_liquidate(
-//     deps: DepsMut,
-//     env: Env,
-//     info: MessageInfo,
-//     offer_assets: AssetList,
-//     receive_asset: AssetInfo,
-//     minimum_receive: Option<Uint128>,
-//     to: Option<String>,
-// ) -> Result<Response, ContractError> {
-//     //Validate input or use sender address if None
-//     let recipient = to.map_or(Ok(info.sender.clone()), |x| deps.api.addr_validate(&x))?;
-
-//     // 1. Assert offer_assets are sent or do TransferFrom on Cw20s
-//     let receive_msgs = receive_assets(&info, &env, &offer_assets)?;
-
-//     // 2. Loop over offer assets and for each:
-//     // Fetch path and call ExecuteMsg::ExecuteSwapOperations
-//     let mut msgs = offer_assets
-//         .into_iter()
-//         .try_fold(vec![], |mut msgs, asset| {
-//             // TODO we should fetch and compare paths here
-//             let path = PATHS
-//                 .load(
-//                     deps.storage,
-//                     (asset.info.clone().into(), receive_asset.clone().into()),
-//                 )
-//                 .map_err(|_| ContractError::NoPathFound {
-//                     offer: asset.info.to_string(),
-//                     ask: receive_asset.to_string(),
-//                 })?;
-//             msgs.extend(path.into_execute_msgs(&env, recipient.clone())?);
-//             Ok::<Vec<_>, ContractError>(msgs)
-//         })?;
-
-//     // 3. Assert min receive
-//     if let Some(minimum_receive) = minimum_receive {
-//         let recipient_balance = receive_asset.query_balance(&deps.querier, recipient.clone())?;
-//         msgs.push(
-//             CallbackMsg::AssertMinimumReceive {
-//                 asset_info: receive_asset.clone(),
-//                 prev_balance: recipient_balance,
-//                 minimum_receive,
-//                 recipient: recipient.clone(),
-//             }
-//             .into_cosmos_msg(&env)?,
-//         );
-//     }
-
-//     let event = Event::new("apollo/cw-dex-router/basket_liquidate")
-//         .add_attribute("offer_assets", offer_assets.to_string())
-//         .add_attribute("receive_asset", receive_asset.to_string())
-//         .add_attribute("minimum_receive", minimum_receive.unwrap_or_default())
-//         .add_attribute("recipient", recipient);
-
-//     Ok(Response::new()
-//         .add_messages(receive_msgs)
-//         .add_messages(msgs)
-//         .add_event(event))
-// }
-
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::SimulateSwapOperations {
-            offer_amount,
-            operations,
-        } => to_json_binary(&simulate_swap_operations(deps, offer_amount, operations)?),
-        // QueryMsg::SimulateBasketLiquidate {
-        //     offer_assets,
-        //     receive_asset,
-        // } => to_json_binary(&simulate_basket_liquidate(
-        //     deps,
-        //     offer_assets,
-        //     receive_asset,
-        // )?),
-        QueryMsg::PathsForPair {
-            offer_asset,
-            ask_asset,
-        } => to_json_binary(&query_paths_for_pair(
-            deps,
-            offer_asset.check(deps.api)?,
-            ask_asset.check(deps.api)?,
-        )?),
-        QueryMsg::BestPathForPair {
-            offer_asset,
-            offer_amount,
-            ask_asset,
-            exclude_paths,
-        } => to_json_binary(&query_best_path_for_pair(
-            deps,
-            offer_amount,
-            offer_asset.check(deps.api)?,
-            ask_asset.check(deps.api)?,
-            exclude_paths,
-        )?),
-        QueryMsg::SupportedOfferAssets { ask_asset } => {
-            to_json_binary(&query_supported_offer_assets(deps, ask_asset)?)
+/// Returns a callback that refunds to `recipient` whatever the swaps leave of
+/// `asset_infos`. `received` are the amounts of the offer assets the contract
+/// already holds for the swaps, which are not part of its previous balance.
+fn refund_leftover_msg(
+    deps: Deps,
+    env: &Env,
+    asset_infos: Vec<AssetInfo>,
+    received: &[Asset],
+    recipient: &Addr,
+) -> Result<CosmosMsg, ContractError> {
+    let mut prev_balances: Vec<Asset> = vec![];
+    for asset_info in asset_infos {
+        if prev_balances.iter().any(|asset| asset.info == asset_info) {
+            continue;
         }
-        QueryMsg::SupportedAskAssets { offer_asset } => {
-            to_json_binary(&query_supported_ask_assets(deps, offer_asset)?)
+        let mut balance =
+            asset_info.query_balance(&deps.querier, env.contract.address.to_string())?;
+        if let Some(received) = received.iter().find(|asset| asset.info == asset_info) {
+            balance = balance.checked_sub(received.amount)?;
         }
+        prev_balances.push(Asset::new(asset_info, balance));
+    }
+
+    CallbackMsg::RefundLeftover {
+        prev_balances: prev_balances.into(),
+        recipient: recipient.clone(),
     }
+    .into_cosmos_msg(env)
 }
 
-pub fn simulate_swap_operations(
+/// Returns the messages that swap exactly `offer_amount` of the offer asset
+/// held by the contract through `operations` and send the output to
+/// `recipient`, and the final message that asserts the minimum receive.
+fn swap_operations_msgs(
     deps: Deps,
-    mut offer_amount: Uint128,
-    operations: SwapOperationsListUnchecked,
-) -> Result<Uint128, ContractError> {
-    let operations = operations.check(deps)?;
+    env: &Env,
+    operations: &SwapOperationsList,
+    offer_amount: Uint128,
+    minimum_receive: Option<Uint128>,
+    recipient: &Addr,
+) -> Result<(Vec<CosmosMsg>, SubMsg), ContractError> {
+    let target_asset_info = operations.to();
+    let offer_asset_info = operations.from();
 
-    for operation in operations.into_iter() {
-        let offer_asset = Asset::new(operation.offer_asset_info, offer_amount);
+    let (swap_recipient, fee_msgs) =
+        swap_recipient_and_fee_msgs(deps, env, &target_asset_info, recipient)?;
+    let mut msgs = vec![operations.into_execute_msg(env, swap_recipient, offer_amount)?];
+    msgs.extend(fee_msgs);
 
-        offer_amount =
-            operation
-                .pool
-                .as_trait()
-                .simulate_swap(deps, offer_asset, operation.ask_asset_info)?;
-    }
+    // Assert min receive
+    let result_msg = swap_result_msg(
+        deps,
+        env,
+        &target_asset_info,
+        vec![Asset::new(offer_asset_info, offer_amount)].into(),
+        minimum_receive,
+        recipient,
+    )?;
 
-    Ok(offer_amount)
+    Ok((msgs, result_msg))
 }
 
-// todo, decide whether I care about basket liquidate in the router
-// pub fn simulate_basket_liquidate(
-//     deps: Deps,
-//     offer_assets: AssetListUnchecked,
-//     receive_asset: AssetInfoUnchecked,
-// ) -> Result<Uint128, ContractError> {
-//     let offer_assets = offer_assets.check(deps.api)?;
-//     let receive_asset = receive_asset.check(deps.api)?;
-
-//     let mut receive_amount = Uint128::zero();
-
-//     // Loop over offer assets and fetch path for each
-//     // for each set of paths between to assets, figure out what the best path is
-//     let paths = offer_assets
-//         .into_iter()
-//         .map(|asset| {
-//             Ok::<_, ContractError>((
-//                 asset.clone(),
-//                 query_paths_for_pair(deps, asset.info.clone(), receive_asset.clone())?,
-//             ))
-//         })
-//         .collect::<Result<Vec<(Asset, SwapOperationsList, _)>, _>>()?;
-
-//     // Loop over paths and simulate swap operations
-//     for (asset, path) in paths {
-//         receive_amount += simulate_swap_operations(deps, asset.amount, path.into())?;
-//     }
-
-//     Ok(receive_amount)
-// }
-
-pub fn query_paths_for_pair(
+/// Returns the final message of a swap, which asserts that `recipient`
+/// received at least `minimum_receive`, if given, and reports the result. It
+/// is sent as a submessage, so its reply can return the received amount as
+/// the data of the swap.
+fn swap_result_msg(
     deps: Deps,
-    offer_asset: AssetInfo,
-    ask_asset: AssetInfo,
-) -> Result<Vec<(u64, SwapOperationsList)>, ContractError> {
-    let ps: StdResult<Vec<(u64, SwapOperationsList)>> = PATHS
-        .prefix(((&offer_asset).into(), (&ask_asset).into()))
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect();
-    let paths = ps?;
-    if paths.is_empty() {
-        Err(ContractError::NoPathFound {
-            offer: offer_asset.to_string(),
-            ask: ask_asset.to_string(),
-        })
-    } else {
-        Ok(paths)
+    env: &Env,
+    asset_info: &AssetInfo,
+    token_in: AssetList,
+    minimum_receive: Option<Uint128>,
+    recipient: &Addr,
+) -> Result<SubMsg, ContractError> {
+    let recipient_balance = asset_info.query_balance(&deps.querier, recipient.to_string())?;
+    let msg = CallbackMsg::AssertMinimumReceive {
+        asset_info: asset_info.clone(),
+        prev_balance: recipient_balance,
+        token_in,
+        minimum_receive,
+        recipient: recipient.clone(),
     }
+    .into_cosmos_msg(env)?;
+
+    Ok(SubMsg::reply_on_success(msg, SWAP_RESULT_REPLY_ID))
 }
 
-pub fn query_best_path_for_pair(
-    deps: Deps,
-    offer_amount: Uint128,
-    offer_asset: AssetInfo,
+/// Finds the best stored path for the given pair and executes it. `info` is
+/// `None` if the offer asset was received through the Cw20 receive hook, in
+/// which case the tokens are already in the contract and are not pulled or
+/// checked against the sent funds.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_best_path(
+    deps: DepsMut,
+    env: Env,
+    info: Option<MessageInfo>,
+    sender: Addr,
+    offer_asset: Asset,
     ask_asset: AssetInfo,
+    minimum_receive: Option<Uint128>,
+    to: Option<String>,
     exclude_paths: Option<Vec<u64>>,
-) -> Result<Option<BestPathForPairResponse>, ContractError> {
-    let paths = query_paths_for_pair(deps, offer_asset, ask_asset)?;
-    let excluded = exclude_paths.unwrap_or(vec![]);
-    let paths: Vec<(u64, SwapOperationsList)> = paths
-        .into_iter()
-        .filter(|(id, _)| !excluded.contains(id))
-        .collect();
+) -> Result<Response, ContractError> {
+    let best_path = find_best_path(
+        deps.as_ref(),
+        offer_asset.amount,
+        offer_asset.info.clone(),
+        ask_asset,
+        exclude_paths,
+    )?;
+
+    let event = Event::new("apollo/cw-dex-router/swap_best_path")
+        .add_attribute("offer_asset", offer_asset.to_string())
+        .add_attribute("return_amount", best_path.return_amount);
+
+    let response = match info {
+        Some(info) => execute_swap_operations(
+            deps,
+            env,
+            info,
+            sender,
+            best_path.operations,
+            Some(offer_asset.amount),
+            minimum_receive,
+            None,
+            None,
+            to,
+        )?,
+        None => execute_received_swap_operations(
+            deps,
+            env,
+            sender,
+            best_path.operations,
+            offer_asset.amount,
+            minimum_receive,
+            None,
+            None,
+            to,
+        )?,
+    };
+
+    Ok(response.add_event(event))
+}
+
+/// Swaps the smallest amount of the offer asset for which `recipient`
+/// receives at least `ask_amount`, and refunds the unspent offer asset.
+pub fn execute_swap_operations_exact_out(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operations: SwapOperationsList,
+    ask_amount: Uint128,
+    max_offer: Uint128,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    //Validate input or use sender address if None
+    let recipient = to.map_or(Ok(info.sender.clone()), |x| deps.api.addr_validate(&x))?;
+
+    let offer_asset_info = operations.from();
+    assert_only_offer_funds(&info, &[offer_asset_info.clone()])?;
+
+    let offer_amount = simulate_reverse_path(deps.as_ref(), ask_amount, &operations)?.offer_amount;
+    if offer_amount > max_offer {
+        return Err(ContractError::MaxOfferExceeded {
+            offer_amount,
+            max_offer,
+        });
+    }
+
+    // 1. Assert `max_offer` of the native offer asset was sent or do
+    // TransferFrom on the needed amount of a Cw20
+    let received_amount = match offer_asset_info {
+        AssetInfo::Native(_) => max_offer,
+        AssetInfo::Cw20(_) => offer_amount,
+    };
+    let mut msgs = receive_asset(
+        &info,
+        &env,
+        &Asset::new(offer_asset_info.clone(), received_amount),
+    )?;
+
+    // 2. Swap the needed amount and assert the ask amount is received
+    let (swap_msgs, result_msg) = swap_operations_msgs(
+        deps.as_ref(),
+        &env,
+        &operations,
+        offer_amount,
+        Some(ask_amount),
+        &recipient,
+    )?;
+    msgs.extend(swap_msgs);
+
+    // 3. Refund the unspent offer asset
+    let held_amount = match offer_asset_info {
+        AssetInfo::Native(_) => max_offer,
+        AssetInfo::Cw20(_) => Uint128::zero(),
+    };
+    msgs.push(refund_leftover_msg(
+        deps.as_ref(),
+        &env,
+        operations.offer_asset_infos(),
+        &[Asset::new(offer_asset_info.clone(), held_amount)],
+        &info.sender,
+    )?);
+
+    let event = Event::new("apollo/cw-dex-router/execute_swap_operations_exact_out")
+        .add_attribute(
+            "offer_asset",
+            Asset::new(offer_asset_info, offer_amount).to_string(),
+        )
+        .add_attribute("max_offer", max_offer)
+        .add_attribute(
+            "ask_asset",
+            Asset::new(operations.to(), ask_amount).to_string(),
+        )
+        .add_attribute("recipient", recipient);
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_submessage(result_msg)
+        .add_event(event))
+}
+
+pub fn execute_split_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    routes: Vec<(Uint128, SwapOperationsList)>,
+    minimum_receive: Option<Uint128>,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    //Validate input or use sender address if None
+    let recipient = to.map_or(Ok(info.sender.clone()), |x| deps.api.addr_validate(&x))?;
+
+    let (_, first_route) = &routes[0];
+    let offer_asset_info = first_route.from();
+    let target_asset_info = first_route.to();
+    let total_offer_amount = routes
+        .iter()
+        .try_fold(Uint128::zero(), |total, (amount, _)| {
+            total.checked_add(*amount)
+        })?;
+    let token_in = Asset::new(offer_asset_info.clone(), total_offer_amount);
+
+    // 1. Assert the native offer asset was sent or do TransferFrom on a Cw20
+    assert_only_offer_funds(&info, &[offer_asset_info.clone()])?;
+    let mut msgs = receive_asset(&info, &env, &token_in)?;
+
+    // 2. Execute each route with its own share of the offer amount
+    let (swap_recipient, fee_msgs) =
+        swap_recipient_and_fee_msgs(deps.as_ref(), &env, &target_asset_info, &recipient)?;
+    for (amount, operations) in routes.iter() {
+        msgs.push(operations.into_execute_msg(&env, swap_recipient.clone(), *amount)?);
+    }
+    msgs.extend(fee_msgs);
+
+    // 3. Assert min receive on the combined output of all routes
+    let result_msg = swap_result_msg(
+        deps.as_ref(),
+        &env,
+        &target_asset_info,
+        vec![token_in.clone()].into(),
+        minimum_receive,
+        &recipient,
+    )?;
+
+    // 4. Refund what is left of the offer and intermediate assets
+    let received_amount = match offer_asset_info {
+        AssetInfo::Native(_) => total_offer_amount,
+        AssetInfo::Cw20(_) => Uint128::zero(),
+    };
+    msgs.push(refund_leftover_msg(
+        deps.as_ref(),
+        &env,
+        routes
+            .iter()
+            .flat_map(|(_, operations)| operations.offer_asset_infos())
+            .collect(),
+        &[Asset::new(offer_asset_info, received_amount)],
+        &info.sender,
+    )?);
+
+    let event = Event::new("apollo/cw-dex-router/execute_split_swap")
+        .add_attribute("offer_asset", token_in.to_string())
+        .add_attribute("ask_asset", target_asset_info.to_string())
+        .add_attribute("routes", routes.len().to_string())
+        .add_attribute("minimum_receive", minimum_receive.unwrap_or_default())
+        .add_attribute("recipient", recipient);
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_submessage(result_msg)
+        .add_event(event))
+}
+
+/// Validates the routes of a split swap. All routes must swap a non-zero
+/// amount and have the same offer and ask assets.
+pub fn check_split_routes(
+    deps: Deps,
+    routes: Vec<(Uint128, SwapOperationsListUnchecked)>,
+) -> Result<Vec<(Uint128, SwapOperationsList)>, ContractError> {
+    let routes = routes
+        .into_iter()
+        .map(|(amount, operations)| Ok((amount, operations.check(deps)?)))
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    let (_, first_route) = routes.first().ok_or(ContractError::MustProvideOperations)?;
+    let offer_asset_info = first_route.from();
+    let target_asset_info = first_route.to();
+
+    for (amount, operations) in routes.iter() {
+        if amount.is_zero() {
+            return Err(ContractError::InvalidSplitRoutes {
+                reason: "Every route must have a non-zero offer amount".to_string(),
+            });
+        }
+        if operations.from() != offer_asset_info || operations.to() != target_asset_info {
+            return Err(ContractError::InvalidSplitRoutes {
+                reason: "All routes must have the same offer and ask assets".to_string(),
+            });
+        }
+    }
+
+    Ok(routes)
+}
+
+/// Returns the address the last swap operation should send its output to. If
+/// a protocol fee is configured, the output is sent to the contract and a
+/// `CollectFee` callback is returned that forwards it to `recipient`.
+fn swap_recipient_and_fee_msgs(
+    deps: Deps,
+    env: &Env,
+    ask_asset_info: &AssetInfo,
+    recipient: &Addr,
+) -> Result<(Addr, Vec<CosmosMsg>), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.fee_bps == 0 {
+        return Ok((recipient.clone(), vec![]));
+    }
+
+    let prev_balance =
+        ask_asset_info.query_balance(&deps.querier, env.contract.address.to_string())?;
+    let fee_msg = CallbackMsg::CollectFee {
+        asset_info: ask_asset_info.clone(),
+        prev_balance,
+        recipient: recipient.clone(),
+    }
+    .into_cosmos_msg(env)?;
+
+    Ok((env.contract.address.clone(), vec![fee_msg]))
+}
+
+pub fn collect_fee(
+    deps: DepsMut,
+    env: Env,
+    asset_info: AssetInfo,
+    prev_balance: Uint128,
+    recipient: Addr,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let balance = asset_info.query_balance(&deps.querier, env.contract.address.to_string())?;
+    let received_amount = balance.checked_sub(prev_balance)?;
+    let fee_amount = received_amount.multiply_ratio(config.fee_bps, BPS_DENOMINATOR);
+    let net_amount = received_amount.checked_sub(fee_amount)?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !fee_amount.is_zero() {
+        msgs.push(
+            Asset::new(asset_info.clone(), fee_amount)
+                .transfer_msg(config.fee_recipient.to_string())?,
+        );
+    }
+    if !net_amount.is_zero() {
+        msgs.push(Asset::new(asset_info.clone(), net_amount).transfer_msg(recipient.to_string())?);
+    }
+
+    let event = Event::new("apollo/cw-dex-router/collect_fee")
+        .add_attribute("asset_info", asset_info.to_string())
+        .add_attribute("fee_amount", fee_amount)
+        .add_attribute("net_amount", net_amount)
+        .add_attribute("fee_recipient", config.fee_recipient)
+        .add_attribute("recipient", recipient);
+
+    Ok(Response::new().add_messages(msgs).add_event(event))
+}
+
+pub fn execute_swap_operation(
+    deps: DepsMut,
+    env: Env,
+    operations: SwapOperationsList,
+    to: Addr,
+    offer_amount: Uint128,
+    hop: u32,
+) -> Result<Response, ContractError> {
+    if offer_amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    // Every operation swaps to the contract. Once all swap messages of the
+    // operation replied, the exact amount returned is measured, so that it can
+    // be swapped by the next operation or sent to the recipient.
+    let (operation, rest) = operations.split_first();
+    let prev_balance = operation
+        .ask_asset_info
+        .query_balance(&deps.querier, env.contract.address.to_string())?;
+    let mut response = operation.to_cosmos_response(
+        deps.as_ref(),
+        &env,
+        offer_amount,
+        None,
+        env.contract.address.clone(),
+    )?;
+    let pending = PendingSwap {
+        hop,
+        pending_msgs: response.messages.len() as u32,
+        operations: rest,
+        ask_asset_info: operation.ask_asset_info.clone(),
+        prev_balance,
+        to,
+    };
+
+    // A pool that swaps without messages has already returned the ask asset
+    if pending.pending_msgs == 0 {
+        let next = finish_swap_operation(deps, env, pending)?;
+        return Ok(response
+            .add_submessages(next.messages)
+            .add_events(next.events));
+    }
+
+    PENDING_SWAP.save(deps.storage, &pending)?;
+    for msg in response.messages.iter_mut() {
+        msg.id = SWAP_OPERATION_REPLY_ID;
+        msg.reply_on = ReplyOn::Always;
+    }
+
+    Ok(response)
+}
+
+/// Handles the reply to a swap message of an operation. A failed swap is
+/// reported with the index of its operation. Once all swap messages of the
+/// operation replied, the operation is finished.
+fn swap_operation_reply(
+    deps: DepsMut,
+    env: Env,
+    result: SubMsgResult,
+) -> Result<Response, ContractError> {
+    let mut pending = PENDING_SWAP.load(deps.storage)?;
+    if let SubMsgResult::Err(error) = result {
+        return Err(ContractError::SwapOperationFailed {
+            hop: pending.hop,
+            error,
+        });
+    }
+
+    pending.pending_msgs -= 1;
+    if pending.pending_msgs > 0 {
+        PENDING_SWAP.save(deps.storage, &pending)?;
+        return Ok(Response::new());
+    }
+    PENDING_SWAP.remove(deps.storage);
+
+    finish_swap_operation(deps, env, pending)
+}
+
+/// Measures the amount returned by a swapped operation. Executes the next
+/// operation with the exact amount returned, or sends it to the recipient if
+/// it was the last operation.
+fn finish_swap_operation(
+    deps: DepsMut,
+    env: Env,
+    pending: PendingSwap,
+) -> Result<Response, ContractError> {
+    let return_amount = pending
+        .ask_asset_info
+        .query_balance(&deps.querier, env.contract.address.to_string())?
+        .checked_sub(pending.prev_balance)?;
+
+    let event = Event::new("apollo/cw-dex-router/swap_operation_reply")
+        .add_attribute("hop", pending.hop.to_string())
+        .add_attribute("ask_asset", pending.ask_asset_info.to_string())
+        .add_attribute("return_amount", return_amount);
+
+    let response = match pending.operations {
+        Some(operations) => execute_swap_operation(
+            deps,
+            env,
+            operations,
+            pending.to,
+            return_amount,
+            pending.hop + 1,
+        )?,
+        None if pending.to != env.contract.address && !return_amount.is_zero() => Response::new()
+            .add_message(
+                Asset::new(pending.ask_asset_info, return_amount)
+                    .transfer_msg(pending.to.to_string())?,
+            ),
+        None => Response::new(),
+    };
+
+    Ok(response.add_event(event))
+}
+
+pub fn refund_leftover(
+    deps: DepsMut,
+    env: Env,
+    prev_balances: AssetList,
+    recipient: Addr,
+) -> Result<Response, ContractError> {
+    let mut refunds: Vec<Asset> = vec![];
+    for prev_balance in &prev_balances {
+        let balance = prev_balance
+            .info
+            .query_balance(&deps.querier, env.contract.address.to_string())?;
+        let leftover = balance.saturating_sub(prev_balance.amount);
+        if !leftover.is_zero() {
+            refunds.push(Asset::new(prev_balance.info.clone(), leftover));
+        }
+    }
+
+    let msgs = refunds
+        .iter()
+        .map(|asset| asset.transfer_msg(recipient.to_string()))
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+    let refunds: AssetList = refunds.into();
+
+    let event = Event::new("apollo/cw-dex-router/refund_leftover")
+        .add_attribute("refunded", refunds.to_string())
+        .add_attribute("recipient", recipient);
+
+    Ok(Response::new().add_messages(msgs).add_event(event))
+}
+
+pub fn assert_minimum_receive(
+    deps: DepsMut,
+    asset_info: AssetInfo,
+    prev_balance: Uint128,
+    token_in: AssetList,
+    minimum_receive: Option<Uint128>,
+    recipient: Addr,
+) -> Result<Response, ContractError> {
+    let recipient_balance = asset_info.query_balance(&deps.querier, recipient.clone())?;
+
+    let received_amount = recipient_balance.checked_sub(prev_balance)?;
+
+    if let Some(minimum_receive) = minimum_receive {
+        if received_amount < minimum_receive {
+            return Err(ContractError::FailedMinimumReceive {
+                token_in,
+                wanted: Asset::new(asset_info.clone(), minimum_receive),
+                got: Asset::new(asset_info, received_amount),
+            });
+        }
+    }
+
+    let event = Event::new("apollo/cw-dex-router/swap_result")
+        .add_attribute("token_in", token_in.to_string())
+        .add_attribute(
+            "token_out",
+            Asset::new(asset_info, received_amount).to_string(),
+        )
+        .add_attribute("recipient", recipient);
+
+    Ok(Response::new()
+        .add_event(event)
+        .set_data(to_json_binary(&SwapResponse {
+            return_amount: received_amount,
+        })?))
+}
+
+/// Forwards the data of the final callback of a swap, so that it becomes
+/// the data of the swap.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, ContractError> {
+    match reply.id {
+        SWAP_OPERATION_REPLY_ID => swap_operation_reply(deps, env, reply.result),
+        SWAP_RESULT_REPLY_ID => {
+            let data = reply
+                .result
+                .into_result()
+                .map_err(StdError::generic_err)?
+                .data
+                .ok_or_else(|| StdError::generic_err("Missing swap result data"))?;
+            let response = parse_execute_response_data(&data)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+            let mut res = Response::new();
+            if let Some(data) = response.data {
+                res = res.set_data(data);
+            }
+            Ok(res)
+        }
+        id => Err(StdError::generic_err(format!("Unknown reply id: {}", id)).into()),
+    }
+}
+
+pub fn propose_new_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_admin: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let new_admin = deps.api.addr_validate(&new_admin)?;
+    PENDING_ADMIN.save(deps.storage, &new_admin)?;
+
+    let event = Event::new("apollo/cw-dex-router/propose_new_admin")
+        .add_attribute("admin", info.sender)
+        .add_attribute("pending_admin", new_admin);
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn accept_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let pending_admin = PENDING_ADMIN
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoAdminProposal)?;
+    if info.sender != pending_admin {
+        return Err(ContractError::Unauthorized);
+    }
+
+    PENDING_ADMIN.remove(deps.storage);
+    ADMIN.set(deps, Some(pending_admin.clone()))?;
+
+    let event =
+        Event::new("apollo/cw-dex-router/accept_admin").add_attribute("admin", pending_admin);
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn clear_proposal(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new().add_event(Event::new("apollo/cw-dex-router/clear_proposal")))
+}
+
+pub fn renounce_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    // Only the admin and the guardian can unpause, and both are removed here,
+    // so renouncing while paused would freeze the router for good
+    if PAUSED.may_load(deps.storage)?.unwrap_or(false) {
+        return Err(ContractError::RenounceWhilePaused);
+    }
+
+    // The path managers and the guardian are appointed by the admin, so they
+    // lose their roles together with it
+    let path_managers = PATH_MANAGERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for path_manager in &path_managers {
+        PATH_MANAGERS.remove(deps.storage, path_manager);
+    }
+    GUARDIAN.remove(deps.storage);
+    PENDING_ADMIN.remove(deps.storage);
+    ADMIN.set(deps, None)?;
+
+    let event =
+        Event::new("apollo/cw-dex-router/renounce_admin").add_attribute("admin", info.sender);
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn query_admin(deps: Deps) -> Result<AdminResponse, ContractError> {
+    Ok(AdminResponse {
+        admin: ADMIN.get(deps)?,
+        pending_admin: PENDING_ADMIN.may_load(deps.storage)?,
+    })
+}
+
+/// Asserts that `sender` is the admin or a path manager.
+fn assert_path_manager(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    if ADMIN.is_admin(deps, sender)? || PATH_MANAGERS.has(deps.storage, sender) {
+        Ok(())
+    } else {
+        Err(ContractError::Unauthorized)
+    }
+}
+
+pub fn add_path_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    PATH_MANAGERS.save(deps.storage, &address, &Empty {})?;
+
+    let event =
+        Event::new("apollo/cw-dex-router/add_path_manager").add_attribute("address", address);
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn remove_path_manager(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    if !PATH_MANAGERS.has(deps.storage, &address) {
+        return Err(ContractError::NotPathManager {
+            address: address.to_string(),
+        });
+    }
+    PATH_MANAGERS.remove(deps.storage, &address);
+
+    let event =
+        Event::new("apollo/cw-dex-router/remove_path_manager").add_attribute("address", address);
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn query_path_managers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<Addr>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    Ok(PATH_MANAGERS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?)
+}
+
+fn assert_admin_or_guardian(deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+    let is_guardian = GUARDIAN.may_load(deps.storage)?.as_ref() == Some(sender);
+    if is_guardian || ADMIN.is_admin(deps, sender)? {
+        Ok(())
+    } else {
+        Err(ContractError::Unauthorized)
+    }
+}
+
+fn assert_not_paused(deps: Deps) -> Result<(), ContractError> {
+    if PAUSED.may_load(deps.storage)?.unwrap_or(false) {
+        Err(ContractError::Paused)
+    } else {
+        Ok(())
+    }
+}
+
+pub fn set_guardian(
+    deps: DepsMut,
+    info: MessageInfo,
+    guardian: Option<String>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let event = match guardian {
+        Some(guardian) => {
+            let guardian = deps.api.addr_validate(&guardian)?;
+            GUARDIAN.save(deps.storage, &guardian)?;
+            Event::new("apollo/cw-dex-router/set_guardian").add_attribute("guardian", guardian)
+        }
+        None => {
+            GUARDIAN.remove(deps.storage);
+            Event::new("apollo/cw-dex-router/set_guardian").add_attribute("guardian", "none")
+        }
+    };
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    assert_admin_or_guardian(deps.as_ref(), &info.sender)?;
+
+    PAUSED.save(deps.storage, &paused)?;
+
+    let action = if paused { "pause" } else { "unpause" };
+    let event =
+        Event::new(format!("apollo/cw-dex-router/{action}")).add_attribute("sender", info.sender);
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn block_pool(deps: DepsMut, info: MessageInfo, pool: Pool) -> Result<Response, ContractError> {
+    assert_admin_or_guardian(deps.as_ref(), &info.sender)?;
+
+    BLOCKED_POOLS.save(deps.storage, blocked_pool_key(&pool)?, &Empty {})?;
+
+    let event =
+        Event::new("apollo/cw-dex-router/block_pool").add_attribute("pool", to_json_string(&pool)?);
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn unblock_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool: Pool,
+) -> Result<Response, ContractError> {
+    assert_admin_or_guardian(deps.as_ref(), &info.sender)?;
+
+    BLOCKED_POOLS.remove(deps.storage, blocked_pool_key(&pool)?);
+
+    let event = Event::new("apollo/cw-dex-router/unblock_pool")
+        .add_attribute("pool", to_json_string(&pool)?);
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn query_blocked_pools(
+    deps: Deps,
+    start_after: Option<Pool>,
+    limit: Option<u32>,
+) -> Result<Vec<Pool>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(blocked_pool_key)
+        .transpose()?
+        .map(Bound::exclusive);
+
+    BLOCKED_POOLS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| Ok(from_json::<Pool>(key?)?))
+        .collect()
+}
+
+pub fn query_pause_info(deps: Deps) -> Result<PauseInfoResponse, ContractError> {
+    Ok(PauseInfoResponse {
+        paused: PAUSED.may_load(deps.storage)?.unwrap_or(false),
+        guardian: GUARDIAN.may_load(deps.storage)?,
+    })
+}
+
+/// Updates the allowlist or the denylist of assets, `name` is only used for
+/// the event.
+pub fn update_asset_list(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset_list: Map<AssetInfoKey, Empty>,
+    name: &str,
+    add: Vec<AssetInfoUnchecked>,
+    remove: Vec<AssetInfoUnchecked>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut event = Event::new(format!("apollo/cw-dex-router/update_{name}_assets"));
+    for asset in add {
+        let asset = asset.check(deps.api)?;
+        asset_list.save(deps.storage, (&asset).into(), &Empty {})?;
+        event = event.add_attribute("add", asset.to_string());
+    }
+    for asset in remove {
+        let asset = asset.check(deps.api)?;
+        asset_list.remove(deps.storage, (&asset).into());
+        event = event.add_attribute("remove", asset.to_string());
+    }
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn query_asset_list(
+    deps: Deps,
+    asset_list: Map<AssetInfoKey, Empty>,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
+) -> Result<Vec<AssetInfo>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|asset| Bound::exclusive(AssetInfoKey::from(asset)));
+
+    Ok(asset_list
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?)
+}
+
+fn assert_asset_allowed(deps: Deps, asset: &AssetInfo) -> Result<(), ContractError> {
+    if is_asset_allowed(deps.storage, asset)? {
+        Ok(())
+    } else {
+        Err(ContractError::AssetNotAllowed {
+            asset: asset.to_string(),
+        })
+    }
+}
+
+/// Checks that a stored path does not swap through a blocked pool or route a
+/// disallowed asset, which may have changed since the path was stored.
+fn assert_stored_path_usable(
+    deps: Deps,
+    operations: &SwapOperationsList,
+) -> Result<(), ContractError> {
+    for pool in operations.pools() {
+        if is_pool_blocked(deps.storage, &pool)? {
+            return Err(ContractError::PoolBlocked { pool });
+        }
+    }
+    for asset in operations.offer_asset_infos() {
+        assert_asset_allowed(deps, &asset)?;
+    }
+    assert_asset_allowed(deps, &operations.to())
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_bps: Option<u16>,
+    fee_recipient: Option<String>,
+    default_hop_penalty: Option<HopPenalty>,
+    clear_default_hop_penalty: Option<bool>,
+    allowlist_enabled: Option<bool>,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(fee_bps) = fee_bps {
+        config.fee_bps = fee_bps;
+    }
+    if let Some(fee_recipient) = fee_recipient {
+        config.fee_recipient = deps.api.addr_validate(&fee_recipient)?;
+    }
+    if clear_default_hop_penalty == Some(true) {
+        if default_hop_penalty.is_some() {
+            return Err(ContractError::HopPenaltySetAndCleared);
+        }
+        config.default_hop_penalty = None;
+    }
+    if let Some(default_hop_penalty) = default_hop_penalty {
+        config.default_hop_penalty = Some(default_hop_penalty);
+    }
+    if let Some(allowlist_enabled) = allowlist_enabled {
+        config.allowlist_enabled = allowlist_enabled;
+    }
+    validate_config(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    let event = Event::new("apollo/cw-dex-router/update_config")
+        .add_attribute("fee_bps", config.fee_bps.to_string())
+        .add_attribute("fee_recipient", config.fee_recipient)
+        .add_attribute(
+            "default_hop_penalty",
+            to_json_string(&config.default_hop_penalty)?,
+        )
+        .add_attribute("allowlist_enabled", config.allowlist_enabled.to_string());
+
+    Ok(Response::new().add_event(event))
+}
+
+fn validate_config(config: &Config) -> Result<(), ContractError> {
+    if config.fee_bps > MAX_FEE_BPS {
+        return Err(ContractError::InvalidFee {
+            fee_bps: config.fee_bps,
+            max_fee_bps: MAX_FEE_BPS,
+        });
+    }
+    if let Some(hop_penalty) = &config.default_hop_penalty {
+        validate_hop_penalty(hop_penalty)?;
+    }
+    Ok(())
+}
+
+fn validate_hop_penalty(hop_penalty: &HopPenalty) -> Result<(), ContractError> {
+    match hop_penalty {
+        HopPenalty::Bps(bps) if *bps > BPS_DENOMINATOR => Err(ContractError::InvalidHopPenalty {
+            bps: *bps,
+            max_bps: BPS_DENOMINATOR,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Asserts that `path` swaps `offer_asset` into `ask_asset` and that none of
+/// its operations has a `min_out` or `max_price_impact`. These are checked
+/// against the amount of a single swap, so they can not be stored in a path
+/// that is reused for any amount.
+fn assert_path_for_pair(
+    path: &SwapOperationsList,
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+) -> Result<(), ContractError> {
+    if &path.from() != offer_asset || &path.to() != ask_asset {
+        return Err(ContractError::InvalidSwapOperations {
+            operations: path.into(),
+            reason: "The path does not match the offer and ask assets".to_string(),
+        });
+    }
+    if path.has_limits() {
+        return Err(ContractError::InvalidSwapOperations {
+            operations: path.into(),
+            reason: "Stored paths can not have a min_out or max_price_impact".to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn set_path(
+    deps: DepsMut,
+    info: MessageInfo,
+    offer_asset: AssetInfo,
+    ask_asset: AssetInfo,
+    path: SwapOperationsList,
+    bidirectional: bool,
+) -> Result<Response, ContractError> {
+    assert_path_manager(deps.as_ref(), &info.sender)?;
+
+    // Validate the path
+    assert_path_for_pair(&path, &offer_asset, &ask_asset)?;
+    assert_asset_allowed(deps.as_ref(), &offer_asset)?;
+    assert_asset_allowed(deps.as_ref(), &ask_asset)?;
+
+    let new_id = next_path_id(deps.storage, &offer_asset, &ask_asset)?;
+    PATHS.save(
+        deps.storage,
+        ((&offer_asset).into(), (&ask_asset).into(), new_id),
+        &path,
+    )?;
+
+    // reverse path and store if `bidirectional` is true
+    if bidirectional {
+        let new_id = next_path_id(deps.storage, &ask_asset, &offer_asset)?;
+        PATHS.save(
+            deps.storage,
+            (ask_asset.into(), offer_asset.into(), new_id),
+            &path.reverse(),
+        )?;
+    }
+    update_offer_assets_by_ask(deps.storage, &offer_asset, &ask_asset)?;
+    update_offer_assets_by_ask(deps.storage, &ask_asset, &offer_asset)?;
+
+    Ok(Response::default())
+}
+
+pub fn remove_path(
+    deps: DepsMut,
+    info: MessageInfo,
+    offer_asset: AssetInfo,
+    ask_asset: AssetInfo,
+    path_id: u64,
+    bidirectional: bool,
+) -> Result<Response, ContractError> {
+    assert_path_manager(deps.as_ref(), &info.sender)?;
+
+    let path = load_path(deps.storage, &offer_asset, &ask_asset, path_id)?;
+    PATHS.remove(
+        deps.storage,
+        ((&offer_asset).into(), (&ask_asset).into(), path_id),
+    );
+
+    // remove the reverse path if `bidirectional` is true
+    if bidirectional {
+        if let Some(reverse_id) =
+            find_path_id(deps.storage, &ask_asset, &offer_asset, &path.reverse())?
+        {
+            PATHS.remove(
+                deps.storage,
+                ((&ask_asset).into(), (&offer_asset).into(), reverse_id),
+            );
+        }
+    }
+    update_offer_assets_by_ask(deps.storage, &offer_asset, &ask_asset)?;
+    update_offer_assets_by_ask(deps.storage, &ask_asset, &offer_asset)?;
+
+    let event = Event::new("apollo/cw-dex-router/remove_path")
+        .add_attribute("offer_asset", offer_asset.to_string())
+        .add_attribute("ask_asset", ask_asset.to_string())
+        .add_attribute("path_id", path_id.to_string())
+        .add_attribute("bidirectional", bidirectional.to_string());
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn replace_path(
+    deps: DepsMut,
+    info: MessageInfo,
+    offer_asset: AssetInfo,
+    ask_asset: AssetInfo,
+    path_id: u64,
+    path: SwapOperationsList,
+    bidirectional: bool,
+) -> Result<Response, ContractError> {
+    assert_path_manager(deps.as_ref(), &info.sender)?;
+
+    // Validate the path
+    assert_path_for_pair(&path, &offer_asset, &ask_asset)?;
+    assert_asset_allowed(deps.as_ref(), &offer_asset)?;
+    assert_asset_allowed(deps.as_ref(), &ask_asset)?;
+
+    let old_path = load_path(deps.storage, &offer_asset, &ask_asset, path_id)?;
+    PATHS.save(
+        deps.storage,
+        ((&offer_asset).into(), (&ask_asset).into(), path_id),
+        &path,
+    )?;
+
+    // replace the reverse of the old path if `bidirectional` is true, or store
+    // the reversed path under a new id if the old one had no reverse entry
+    if bidirectional {
+        let reverse_id =
+            match find_path_id(deps.storage, &ask_asset, &offer_asset, &old_path.reverse())? {
+                Some(id) => id,
+                None => next_path_id(deps.storage, &ask_asset, &offer_asset)?,
+            };
+        PATHS.save(
+            deps.storage,
+            ((&ask_asset).into(), (&offer_asset).into(), reverse_id),
+            &path.reverse(),
+        )?;
+    }
+    update_offer_assets_by_ask(deps.storage, &ask_asset, &offer_asset)?;
+
+    let event = Event::new("apollo/cw-dex-router/replace_path")
+        .add_attribute("offer_asset", offer_asset.to_string())
+        .add_attribute("ask_asset", ask_asset.to_string())
+        .add_attribute("path_id", path_id.to_string())
+        .add_attribute("bidirectional", bidirectional.to_string());
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Adds the (offer_asset, ask_asset) pair to [`OFFER_ASSETS_BY_ASK`] if it
+/// has a stored path, and removes it otherwise.
+fn update_offer_assets_by_ask(
+    storage: &mut dyn Storage,
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+) -> StdResult<()> {
+    let has_path = PATHS
+        .prefix((offer_asset.into(), ask_asset.into()))
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+
+    if has_path {
+        OFFER_ASSETS_BY_ASK.save(storage, (ask_asset.into(), offer_asset.into()), &Empty {})
+    } else {
+        OFFER_ASSETS_BY_ASK.remove(storage, (ask_asset.into(), offer_asset.into()));
+        Ok(())
+    }
+}
+
+/// Returns the id the next path stored under the (offer_asset, ask_asset)
+/// pair should use and records it as used.
+fn next_path_id(
+    storage: &mut dyn Storage,
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+) -> StdResult<u64> {
+    let id = LAST_PATH_IDS
+        .may_load(storage, (offer_asset.into(), ask_asset.into()))?
+        .unwrap_or(0)
+        + 1;
+    LAST_PATH_IDS.save(storage, (offer_asset.into(), ask_asset.into()), &id)?;
+
+    Ok(id)
+}
+
+fn load_path(
+    storage: &dyn Storage,
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+    path_id: u64,
+) -> Result<SwapOperationsList, ContractError> {
+    PATHS
+        .may_load(storage, (offer_asset.into(), ask_asset.into(), path_id))?
+        .ok_or_else(|| ContractError::PathNotFound {
+            offer: offer_asset.to_string(),
+            ask: ask_asset.to_string(),
+            path_id,
+        })
+}
+
+/// Returns the id under which `path` is stored for the (offer_asset,
+/// ask_asset) pair, if any.
+fn find_path_id(
+    storage: &dyn Storage,
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+    path: &SwapOperationsList,
+) -> StdResult<Option<u64>> {
+    for x in PATHS.prefix((offer_asset.into(), ask_asset.into())).range(
+        storage,
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let (id, stored_path) = x?;
+        if stored_path == *path {
+            return Ok(Some(id));
+        }
+    }
+    Ok(None)
+}
+
+pub fn register_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool: Pool,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let mut pool_count = 0;
+    for x in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, registered) = x?;
+        if registered.pool == pool {
+            return Err(ContractError::PoolAlreadyRegistered { pool });
+        }
+        pool_count += 1;
+    }
+    if pool_count >= MAX_REGISTERED_POOLS {
+        return Err(ContractError::TooManyPools {
+            max: MAX_REGISTERED_POOLS,
+        });
+    }
+
+    let pool_assets = pool.as_trait().pool_assets(deps.as_ref())?;
+
+    let pool_id = LAST_POOL_ID.may_load(deps.storage)?.unwrap_or(0) + 1;
+    LAST_POOL_ID.save(deps.storage, &pool_id)?;
+    POOLS.save(deps.storage, pool_id, &RegisteredPool { pool, pool_assets })?;
+
+    let event = Event::new("apollo/cw-dex-router/register_pool")
+        .add_attribute("pool_id", pool_id.to_string());
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn deregister_pool(
+    deps: DepsMut,
+    info: MessageInfo,
+    pool_id: u64,
+) -> Result<Response, ContractError> {
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if !POOLS.has(deps.storage, pool_id) {
+        return Err(ContractError::PoolNotRegistered { pool_id });
+    }
+    POOLS.remove(deps.storage, pool_id);
+
+    let event = Event::new("apollo/cw-dex-router/deregister_pool")
+        .add_attribute("pool_id", pool_id.to_string());
+
+    Ok(Response::new().add_event(event))
+}
+
+pub fn basket_liquidate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    offer_assets: AssetList,
+    receive_asset: AssetInfo,
+    minimum_receive: Option<Uint128>,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    //Validate input or use sender address if None
+    let recipient = to.map_or(Ok(info.sender.clone()), |x| deps.api.addr_validate(&x))?;
+
+    // 1. Assert exactly the native offer_assets are sent or do TransferFrom
+    // on Cw20s. The native offer assets are then held by the contract.
+    let mut offer_asset_infos: Vec<AssetInfo> = vec![];
+    let mut received: Vec<Asset> = vec![];
+    for asset in &offer_assets {
+        offer_asset_infos.push(asset.info.clone());
+        if let AssetInfo::Native(_) = asset.info {
+            received.push(asset.clone());
+        }
+    }
+    assert_only_offer_funds(&info, &offer_asset_infos)?;
+    let receive_msgs = receive_assets(&info, &env, &offer_assets)?;
+
+    // 2. Loop over offer assets and for each:
+    // Find the best stored path and add the swap messages
+    let (swap_recipient, fee_msgs) =
+        swap_recipient_and_fee_msgs(deps.as_ref(), &env, &receive_asset, &recipient)?;
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut path_asset_infos: Vec<AssetInfo> = vec![];
+    for asset in &offer_assets {
+        let best_path = find_best_path(
+            deps.as_ref(),
+            asset.amount,
+            asset.info.clone(),
+            receive_asset.clone(),
+            None,
+        )?;
+        path_asset_infos.extend(best_path.operations.offer_asset_infos());
+        msgs.push(best_path.operations.into_execute_msg(
+            &env,
+            swap_recipient.clone(),
+            asset.amount,
+        )?);
+    }
+    msgs.extend(fee_msgs);
+
+    // 3. Refund what is left of the offer and intermediate assets
+    msgs.push(refund_leftover_msg(
+        deps.as_ref(),
+        &env,
+        path_asset_infos,
+        &received,
+        &info.sender,
+    )?);
+
+    // 4. Assert min receive
+    let result_msg = swap_result_msg(
+        deps.as_ref(),
+        &env,
+        &receive_asset,
+        offer_assets.clone(),
+        minimum_receive,
+        &recipient,
+    )?;
+
+    let event = Event::new("apollo/cw-dex-router/basket_liquidate")
+        .add_attribute("offer_assets", offer_assets.to_string())
+        .add_attribute("receive_asset", receive_asset.to_string())
+        .add_attribute("minimum_receive", minimum_receive.unwrap_or_default())
+        .add_attribute("recipient", recipient);
+
+    Ok(Response::new()
+        .add_messages(receive_msgs)
+        .add_messages(msgs)
+        .add_submessage(result_msg)
+        .add_event(event))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::SimulateSwapOperations {
+            offer_amount,
+            operations,
+        } => to_json_binary(&simulate_swap_response(
+            deps,
+            simulate_swap_operations(deps, offer_amount, operations)?,
+        )?),
+        QueryMsg::SimulateReverseSwapOperations {
+            ask_amount,
+            operations,
+        } => to_json_binary(&simulate_reverse_swap_operations(
+            deps, ask_amount, operations,
+        )?),
+        QueryMsg::SimulateSplitSwap { routes } => to_json_binary(&simulate_swap_response(
+            deps,
+            simulate_split_swap(deps, routes)?,
+        )?),
+        QueryMsg::SimulateBasketLiquidate {
+            offer_assets,
+            receive_asset,
+        } => to_json_binary(&simulate_swap_response(
+            deps,
+            simulate_basket_liquidate(deps, offer_assets, receive_asset)?,
+        )?),
+        QueryMsg::PathsForPair {
+            offer_asset,
+            ask_asset,
+        } => to_json_binary(&query_paths_for_pair(
+            deps,
+            offer_asset.check(deps.api)?,
+            ask_asset.check(deps.api)?,
+        )?),
+        QueryMsg::SimulatePathsForPair {
+            offer_asset,
+            offer_amount,
+            ask_asset,
+        } => to_json_binary(&query_simulate_paths_for_pair(
+            deps,
+            offer_amount,
+            offer_asset.check(deps.api)?,
+            ask_asset.check(deps.api)?,
+        )?),
+        QueryMsg::BestPathForPair {
+            offer_asset,
+            offer_amount,
+            ask_asset,
+            exclude_paths,
+            hop_penalty,
+        } => to_json_binary(&query_best_path_for_pair(
+            deps,
+            offer_amount,
+            offer_asset.check(deps.api)?,
+            ask_asset.check(deps.api)?,
+            exclude_paths,
+            hop_penalty,
+        )?),
+        QueryMsg::BestSplitForPair {
+            offer_asset,
+            offer_amount,
+            ask_asset,
+            max_splits,
+            granularity,
+        } => to_json_binary(&query_best_split_for_pair(
+            deps,
+            offer_amount,
+            offer_asset.check(deps.api)?,
+            ask_asset.check(deps.api)?,
+            max_splits,
+            granularity,
+        )?),
+        QueryMsg::DiscoverPaths {
+            offer_asset,
+            ask_asset,
+            max_hops,
+            offer_amount,
+        } => to_json_binary(&query_discover_paths(
+            deps,
+            offer_asset.check(deps.api)?,
+            ask_asset.check(deps.api)?,
+            max_hops,
+            offer_amount,
+        )?),
+        QueryMsg::RegisteredPools { start_after, limit } => {
+            to_json_binary(&query_registered_pools(deps, start_after, limit)?)
+        }
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Admin {} => to_json_binary(&query_admin(deps)?),
+        QueryMsg::PauseInfo {} => to_json_binary(&query_pause_info(deps)?),
+        QueryMsg::BlockedPools { start_after, limit } => {
+            to_json_binary(&query_blocked_pools(deps, start_after, limit)?)
+        }
+        QueryMsg::PathManagers { start_after, limit } => {
+            to_json_binary(&query_path_managers(deps, start_after, limit)?)
+        }
+        QueryMsg::AllowedAssets { start_after, limit } => to_json_binary(&query_asset_list(
+            deps,
+            ALLOWED_ASSETS,
+            start_after.map(|x| x.check(deps.api)).transpose()?,
+            limit,
+        )?),
+        QueryMsg::DeniedAssets { start_after, limit } => to_json_binary(&query_asset_list(
+            deps,
+            DENIED_ASSETS,
+            start_after.map(|x| x.check(deps.api)).transpose()?,
+            limit,
+        )?),
+        QueryMsg::SupportedOfferAssets {
+            ask_asset,
+            start_after,
+            limit,
+        } => to_json_binary(&query_supported_offer_assets(
+            deps,
+            ask_asset.check(deps.api)?,
+            start_after.map(|x| x.check(deps.api)).transpose()?,
+            limit,
+        )?),
+        QueryMsg::SupportedAskAssets {
+            offer_asset,
+            start_after,
+            limit,
+        } => to_json_binary(&query_supported_ask_assets(
+            deps,
+            offer_asset.check(deps.api)?,
+            start_after.map(|x| x.check(deps.api)).transpose()?,
+            limit,
+        )?),
+        QueryMsg::AllPaths { start_after, limit } => {
+            let start_after = start_after
+                .map(|(offer_asset, ask_asset, path_id)| {
+                    Ok::<_, StdError>((
+                        offer_asset.check(deps.api)?,
+                        ask_asset.check(deps.api)?,
+                        path_id,
+                    ))
+                })
+                .transpose()?;
+            to_json_binary(&query_all_paths(deps, start_after, limit)?)
+        }
+    }
+}
+
+/// Splits the simulated output of a swap into the protocol fee and the amount
+/// received by the recipient.
+fn simulate_swap_response(
+    deps: Deps,
+    gross_amount: Uint128,
+) -> Result<SimulateSwapResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let fee_amount = gross_amount.multiply_ratio(config.fee_bps, BPS_DENOMINATOR);
+
+    Ok(SimulateSwapResponse {
+        gross_amount,
+        fee_amount,
+        net_amount: gross_amount.checked_sub(fee_amount)?,
+    })
+}
+
+pub fn simulate_swap_operations(
+    deps: Deps,
+    offer_amount: Uint128,
+    operations: SwapOperationsListUnchecked,
+) -> Result<Uint128, ContractError> {
+    simulate_path(deps, offer_amount, operations.check(deps)?)
+}
+
+/// Simulates the swap operations of an already validated path.
+fn simulate_path(
+    deps: Deps,
+    mut offer_amount: Uint128,
+    operations: SwapOperationsList,
+) -> Result<Uint128, ContractError> {
+    for operation in operations.into_iter() {
+        let offer_asset = Asset::new(operation.offer_asset_info, offer_amount);
+
+        offer_amount =
+            operation
+                .pool
+                .as_trait()
+                .simulate_swap(deps, offer_asset, operation.ask_asset_info)?;
+    }
+
+    Ok(offer_amount)
+}
+
+pub fn simulate_reverse_swap_operations(
+    deps: Deps,
+    ask_amount: Uint128,
+    operations: SwapOperationsListUnchecked,
+) -> Result<SimulateReverseSwapResponse, ContractError> {
+    simulate_reverse_path(deps, ask_amount, &operations.check(deps)?)
+}
+
+/// Simulates the swap operations of an already validated path backwards,
+/// starting from the amount the path must return for `ask_amount` to remain
+/// after the protocol fee.
+fn simulate_reverse_path(
+    deps: Deps,
+    ask_amount: Uint128,
+    operations: &SwapOperationsList,
+) -> Result<SimulateReverseSwapResponse, ContractError> {
+    // The fee is rounded down, so the gross amount is rounded up
+    let config = CONFIG.load(deps.storage)?;
+    let mut gross_amount =
+        ask_amount.multiply_ratio(BPS_DENOMINATOR, BPS_DENOMINATOR - config.fee_bps);
+    if simulate_swap_response(deps, gross_amount)?.net_amount < ask_amount {
+        gross_amount = gross_amount.checked_add(Uint128::one())?;
+    }
+    let fee_amount = simulate_swap_response(deps, gross_amount)?.fee_amount;
+
+    let mut amount = gross_amount;
+    for operation in operations.clone().into_iter().rev() {
+        amount = reverse_simulate_operation(deps, &operation, amount)?;
+    }
+
+    Ok(SimulateReverseSwapResponse {
+        offer_amount: amount,
+        gross_amount,
+        fee_amount,
+    })
+}
+
+/// Returns the smallest offer amount for which `operation` returns at least
+/// `ask_amount`. The pools only simulate swaps with an exact offer amount, so
+/// the amount is found with a binary search over forward simulations.
+fn reverse_simulate_operation(
+    deps: Deps,
+    operation: &SwapOperation,
+    ask_amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    if ask_amount.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let simulate = |offer_amount: Uint128| -> Result<Uint128, ContractError> {
+        Ok(operation.pool.as_trait().simulate_swap(
+            deps,
+            Asset::new(operation.offer_asset_info.clone(), offer_amount),
+            operation.ask_asset_info.clone(),
+        )?)
+    };
+
+    // Double the offer amount until it returns enough
+    let mut low = Uint128::zero();
+    let mut high = ask_amount;
+    while simulate(high)? < ask_amount {
+        low = high;
+        high = high.checked_mul(Uint128::new(2)).map_err(|_| {
+            ContractError::InsufficientLiquidity {
+                ask: Asset::new(operation.ask_asset_info.clone(), ask_amount),
+            }
+        })?;
+    }
+
+    // `high` always returns enough, `low` never does
+    while high - low > Uint128::one() {
+        let mid = low + (high - low) / Uint128::new(2);
+        if simulate(mid)? >= ask_amount {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+
+    Ok(high)
+}
+
+pub fn simulate_split_swap(
+    deps: Deps,
+    routes: Vec<(Uint128, SwapOperationsListUnchecked)>,
+) -> Result<Uint128, ContractError> {
+    let routes = check_split_routes(deps, routes)?;
+
+    routes
+        .into_iter()
+        .try_fold(Uint128::zero(), |total, (amount, operations)| {
+            Ok::<_, ContractError>(total.checked_add(simulate_path(deps, amount, operations)?)?)
+        })
+}
+
+pub fn simulate_basket_liquidate(
+    deps: Deps,
+    offer_assets: AssetListUnchecked,
+    receive_asset: AssetInfoUnchecked,
+) -> Result<Uint128, ContractError> {
+    let offer_assets = offer_assets.check(deps.api)?;
+    let receive_asset = receive_asset.check(deps.api)?;
+
+    // Loop over offer assets and add the return amount of the best path for each
+    let mut receive_amount = Uint128::zero();
+    for asset in &offer_assets {
+        let best_path = find_best_path(
+            deps,
+            asset.amount,
+            asset.info.clone(),
+            receive_asset.clone(),
+            None,
+        )?;
+        receive_amount = receive_amount.checked_add(best_path.return_amount)?;
+    }
+
+    Ok(receive_amount)
+}
+
+pub fn query_paths_for_pair(
+    deps: Deps,
+    offer_asset: AssetInfo,
+    ask_asset: AssetInfo,
+) -> Result<Vec<(u64, SwapOperationsList)>, ContractError> {
+    let ps: StdResult<Vec<(u64, SwapOperationsList)>> = PATHS
+        .prefix(((&offer_asset).into(), (&ask_asset).into()))
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect();
+    let paths = ps?;
+    if paths.is_empty() {
+        Err(ContractError::NoPathFound {
+            offer: offer_asset.to_string(),
+            ask: ask_asset.to_string(),
+        })
+    } else {
+        Ok(paths)
+    }
+}
+
+pub fn query_simulate_paths_for_pair(
+    deps: Deps,
+    offer_amount: Uint128,
+    offer_asset: AssetInfo,
+    ask_asset: AssetInfo,
+) -> Result<Vec<PathSimulation>, ContractError> {
+    let paths = query_paths_for_pair(deps, offer_asset, ask_asset)?;
+    Ok(simulate_paths(deps, offer_amount, paths))
+}
+
+/// Simulates `offer_amount` through each of `paths`. A failing simulation is
+/// returned with its error instead of failing the whole simulation. Paths
+/// through a blocked pool or a disallowed asset fail without being simulated.
+fn simulate_paths(
+    deps: Deps,
+    offer_amount: Uint128,
+    paths: Vec<(u64, SwapOperationsList)>,
+) -> Vec<PathSimulation> {
+    paths
+        .into_iter()
+        .map(|(path_id, operations)| {
+            let simulation = assert_stored_path_usable(deps, &operations)
+                .and_then(|_| simulate_path(deps, offer_amount, operations.clone()))
+                .and_then(|return_amount| simulate_swap_response(deps, return_amount));
+            let (simulation, error) = match simulation {
+                Ok(simulation) => (Some(simulation), None),
+                Err(err) => (None, Some(err.to_string())),
+            };
+            PathSimulation {
+                path_id,
+                operations,
+                return_amount: simulation.as_ref().map(|x| x.gross_amount),
+                fee_amount: simulation.as_ref().map(|x| x.fee_amount),
+                net_amount: simulation.map(|x| x.net_amount),
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Returns the path with the highest penalized return. Paths whose
+/// simulation fails are skipped and reported in `failed_paths`, and
+/// `NoPathFound` is only returned if every path fails.
+pub fn query_best_path_for_pair(
+    deps: Deps,
+    offer_amount: Uint128,
+    offer_asset: AssetInfo,
+    ask_asset: AssetInfo,
+    exclude_paths: Option<Vec<u64>>,
+    hop_penalty: Option<HopPenalty>,
+) -> Result<BestPathForPairResponse, ContractError> {
+    let hop_penalty = match hop_penalty {
+        Some(hop_penalty) => {
+            validate_hop_penalty(&hop_penalty)?;
+            Some(hop_penalty)
+        }
+        None => CONFIG.load(deps.storage)?.default_hop_penalty,
+    };
+
+    let paths = query_paths_for_pair(deps, offer_asset.clone(), ask_asset.clone())?;
+    let excluded = exclude_paths.unwrap_or(vec![]);
+    let paths: Vec<(u64, SwapOperationsList)> = paths
+        .into_iter()
+        .filter(|(id, _)| !excluded.contains(id))
+        .collect();
 
     if paths.is_empty() {
         return Err(ContractError::NoPathsToCheck {});
     }
 
-    let swap_paths: Result<Vec<BestPathForPairResponse>, ContractError> = paths
-        .into_iter()
-        .map(|(_, swaps)| {
-            let out = simulate_swap_operations(deps, offer_amount, swaps.clone().into())?;
-            Ok(BestPathForPairResponse {
-                operations: swaps,
-                return_amount: out,
-            })
+    let mut failed_paths = vec![];
+    let mut swap_paths = vec![];
+    for simulation in simulate_paths(deps, offer_amount, paths) {
+        match (simulation.return_amount, simulation.error) {
+            (Some(out), _) => {
+                let penalized_out = match &hop_penalty {
+                    Some(hop_penalty) => hop_penalty.apply(out, simulation.operations.len()),
+                    None => out,
+                };
+                swap_paths.push((simulation.operations, out, penalized_out));
+            }
+            (None, error) => failed_paths.push(FailedPath {
+                path_id: simulation.path_id,
+                error: error.unwrap_or_default(),
+            }),
+        }
+    }
+
+    let (operations, return_amount, penalized_return_amount) = swap_paths
+        .into_iter()
+        .max_by(|a, b| a.2.cmp(&b.2))
+        .ok_or_else(|| ContractError::NoPathFound {
+            offer: offer_asset.to_string(),
+            ask: ask_asset.to_string(),
+        })?;
+
+    let simulation = simulate_swap_response(deps, return_amount)?;
+
+    Ok(BestPathForPairResponse {
+        operations,
+        return_amount,
+        fee_amount: simulation.fee_amount,
+        net_amount: simulation.net_amount,
+        penalized_return_amount,
+        failed_paths,
+    })
+}
+
+/// Same as [`query_best_path_for_pair`] with the default hop penalty.
+pub fn find_best_path(
+    deps: Deps,
+    offer_amount: Uint128,
+    offer_asset: AssetInfo,
+    ask_asset: AssetInfo,
+    exclude_paths: Option<Vec<u64>>,
+) -> Result<BestPathForPairResponse, ContractError> {
+    query_best_path_for_pair(
+        deps,
+        offer_amount,
+        offer_asset,
+        ask_asset,
+        exclude_paths,
+        None,
+    )
+}
+
+pub fn query_best_split_for_pair(
+    deps: Deps,
+    offer_amount: Uint128,
+    offer_asset: AssetInfo,
+    ask_asset: AssetInfo,
+    max_splits: Option<u32>,
+    granularity: Option<u32>,
+) -> Result<BestSplitForPairResponse, ContractError> {
+    // Paths through a blocked pool or a disallowed asset are skipped
+    let mut paths = query_paths_for_pair(deps, offer_asset, ask_asset)?;
+    paths.retain(|(_, operations)| assert_stored_path_usable(deps, operations).is_ok());
+
+    let granularity = granularity.unwrap_or(DEFAULT_SPLIT_GRANULARITY);
+    if granularity == 0 || granularity > MAX_SPLIT_GRANULARITY {
+        return Err(ContractError::InvalidSplitParameters {
+            reason: format!("granularity must be between 1 and {MAX_SPLIT_GRANULARITY}"),
+        });
+    }
+    let max_splits = max_splits.unwrap_or(paths.len() as u32) as usize;
+    if max_splits == 0 {
+        return Err(ContractError::InvalidSplitParameters {
+            reason: "max_splits must be at least 1".to_string(),
+        });
+    }
+    if offer_amount.is_zero() {
+        return Err(ContractError::InvalidSplitParameters {
+            reason: "offer_amount must be non-zero".to_string(),
+        });
+    }
+
+    // Split the offer amount into chunks, the last chunk takes the remainder
+    let chunk_amount = offer_amount / Uint128::from(granularity);
+    let mut chunks = vec![chunk_amount; granularity as usize - 1];
+    chunks.push(offer_amount - chunk_amount * Uint128::from(granularity - 1));
+
+    // The amount allocated to each path and its simulated return
+    let mut allocations = vec![(Uint128::zero(), Uint128::zero()); paths.len()];
+    for chunk in chunks.into_iter().filter(|chunk| !chunk.is_zero()) {
+        let used_paths = allocations
+            .iter()
+            .filter(|(amount, _)| !amount.is_zero())
+            .count();
+
+        // Give the chunk to the path with the best marginal return. Paths that
+        // fail to simulate are skipped.
+        let mut best: Option<(usize, Uint128, Uint128)> = None;
+        for (i, (_, operations)) in paths.iter().enumerate() {
+            let (allocated, current_return) = allocations[i];
+            if allocated.is_zero() && used_paths >= max_splits {
+                continue;
+            }
+            let new_return = match simulate_path(deps, allocated + chunk, operations.clone()) {
+                Ok(new_return) => new_return,
+                Err(_) => continue,
+            };
+            let marginal_return = new_return.saturating_sub(current_return);
+            match best {
+                Some((_, _, best_marginal_return)) if best_marginal_return >= marginal_return => {}
+                _ => best = Some((i, new_return, marginal_return)),
+            }
+        }
+
+        let (i, new_return, _) = best.ok_or(ContractError::NoPathsToCheck {})?;
+        allocations[i] = (allocations[i].0 + chunk, new_return);
+    }
+
+    let mut return_amount = Uint128::zero();
+    let mut routes = vec![];
+    for ((_, operations), (amount, path_return)) in paths.into_iter().zip(allocations) {
+        if !amount.is_zero() {
+            return_amount = return_amount.checked_add(path_return)?;
+            routes.push((amount, operations));
+        }
+    }
+
+    let simulation = simulate_swap_response(deps, return_amount)?;
+
+    Ok(BestSplitForPairResponse {
+        routes,
+        return_amount,
+        fee_amount: simulation.fee_amount,
+        net_amount: simulation.net_amount,
+    })
+}
+
+pub fn query_discover_paths(
+    deps: Deps,
+    offer_asset: AssetInfo,
+    ask_asset: AssetInfo,
+    max_hops: u32,
+    offer_amount: Uint128,
+) -> Result<Vec<DiscoveredPath>, ContractError> {
+    if max_hops == 0 || max_hops > MAX_DISCOVERY_HOPS {
+        return Err(ContractError::InvalidMaxHops {
+            max: MAX_DISCOVERY_HOPS,
+            got: max_hops,
+        });
+    }
+
+    // Blocked pools are left out of the pool graph
+    let mut pools = vec![];
+    for x in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, registered) = x?;
+        if !is_pool_blocked(deps.storage, &registered.pool)? {
+            pools.push(registered);
+        }
+    }
+
+    // Simulate the shortest routes found in the pool graph, skipping routes
+    // that route a disallowed asset and routes whose simulation fails
+    let is_usable =
+        |operations: &SwapOperationsList| assert_stored_path_usable(deps, operations).is_ok();
+    let mut paths: Vec<DiscoveredPath> = find_routes(
+        &pools,
+        &offer_asset,
+        &ask_asset,
+        max_hops,
+        MAX_SIMULATED_ROUTES,
+        &is_usable,
+    )
+    .into_iter()
+    .filter_map(|operations| {
+        let return_amount = simulate_path(deps, offer_amount, operations.clone()).ok()?;
+        let simulation = simulate_swap_response(deps, return_amount).ok()?;
+        Some(DiscoveredPath {
+            operations,
+            return_amount,
+            fee_amount: simulation.fee_amount,
+            net_amount: simulation.net_amount,
         })
-        .collect();
+    })
+    .collect();
+    paths.sort_by(|a, b| b.return_amount.cmp(&a.return_amount));
 
-    let best_path = swap_paths?
-        .into_iter()
-        .max_by(|a, b| a.return_amount.cmp(&b.return_amount));
+    Ok(paths)
+}
+
+pub fn query_registered_pools(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<(u64, RegisteredPool)>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
 
-    Ok(best_path)
+    Ok(POOLS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?)
 }
 
 pub fn query_supported_offer_assets(
     deps: Deps,
-    ask_asset: AssetInfoUnchecked,
+    ask_asset: AssetInfo,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
 ) -> Result<Vec<AssetInfo>, ContractError> {
-    let mut offer_assets: Vec<AssetInfo> = vec![];
-    for x in PATHS.range(deps.storage, None, None, Order::Ascending) {
-        let ((offer_asset, path_ask_asset, _), _) = x?;
-        if path_ask_asset == ask_asset.check(deps.api)? {
-            offer_assets.push(offer_asset.into());
+    if !is_asset_allowed(deps.storage, &ask_asset)? {
+        return Ok(vec![]);
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|offer_asset| Bound::exclusive(AssetInfoKey::from(offer_asset)));
+
+    let mut offer_assets = vec![];
+    for offer_asset in OFFER_ASSETS_BY_ASK.prefix((&ask_asset).into()).keys(
+        deps.storage,
+        start,
+        None,
+        Order::Ascending,
+    ) {
+        let offer_asset = offer_asset?;
+        if !has_usable_path(deps, &offer_asset, &ask_asset)? {
+            continue;
         }
+        if offer_assets.len() == limit {
+            break;
+        }
+        offer_assets.push(offer_asset);
     }
     Ok(offer_assets)
 }
 
 pub fn query_supported_ask_assets(
     deps: Deps,
-    offer_asset: AssetInfoUnchecked,
+    offer_asset: AssetInfo,
+    start_after: Option<AssetInfo>,
+    limit: Option<u32>,
 ) -> Result<Vec<AssetInfo>, ContractError> {
+    if !is_asset_allowed(deps.storage, &offer_asset)? {
+        return Ok(vec![]);
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // Skip all path ids of the last returned ask asset
+    let start = start_after
+        .as_ref()
+        .map(|ask_asset| Bound::exclusive((AssetInfoKey::from(ask_asset), u64::MAX)));
+
+    // Paths to the same ask asset are adjacent, so the remaining paths of an
+    // ask asset are skipped by comparing with the last ask asset
     let mut ask_assets: Vec<AssetInfo> = vec![];
-    for x in PATHS.range(deps.storage, None, None, Order::Ascending) {
-        let ((path_offer_asset, ask_asset, _), _) = x?;
-        if path_offer_asset == offer_asset.check(deps.api)? {
-            ask_assets.push(ask_asset.into());
+    for item in
+        PATHS
+            .sub_prefix((&offer_asset).into())
+            .range(deps.storage, start, None, Order::Ascending)
+    {
+        let ((ask_asset, _), path) = item?;
+        if ask_assets.last() == Some(&ask_asset) || assert_stored_path_usable(deps, &path).is_err()
+        {
+            continue;
         }
+        if ask_assets.len() == limit {
+            break;
+        }
+        ask_assets.push(ask_asset);
     }
     Ok(ask_assets)
 }
 
+/// Returns whether any stored path from `offer_asset` to `ask_asset` passes
+/// [`assert_stored_path_usable`].
+fn has_usable_path(
+    deps: Deps,
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+) -> Result<bool, ContractError> {
+    for path in PATHS.prefix((offer_asset.into(), ask_asset.into())).range(
+        deps.storage,
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let (_, path) = path?;
+        if assert_stored_path_usable(deps, &path).is_ok() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+pub fn query_all_paths(
+    deps: Deps,
+    start_after: Option<(AssetInfo, AssetInfo, u64)>,
+    limit: Option<u32>,
+) -> Result<Vec<(AssetInfo, AssetInfo, u64, SwapOperationsList)>, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .as_ref()
+        .map(|(offer_asset, ask_asset, path_id)| {
+            Bound::exclusive((
+                AssetInfoKey::from(offer_asset),
+                AssetInfoKey::from(ask_asset),
+                *path_id,
+            ))
+        });
+
+    Ok(PATHS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|x| {
+            let ((offer_asset, ask_asset, path_id), path) = x?;
+            Ok((offer_asset, ask_asset, path_id, path))
+        })
+        .collect::<StdResult<Vec<_>>>()?)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // Contracts instantiated before the config was added don't take a fee
+    if !CONFIG.exists(deps.storage) {
+        let fee_recipient = ADMIN.get(deps.as_ref())?.unwrap_or(env.contract.address);
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                fee_bps: 0,
+                fee_recipient,
+                default_hop_penalty: None,
+                allowlist_enabled: false,
+            },
+        )?;
+    }
+
+    // Index the pairs of paths stored before the index was added, and start
+    // the path ids of each pair after the highest stored id
+    let keys = PATHS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (offer_asset, ask_asset, path_id) in keys {
+        OFFER_ASSETS_BY_ASK.save(
+            deps.storage,
+            ((&ask_asset).into(), (&offer_asset).into()),
+            &Empty {},
+        )?;
+        LAST_PATH_IDS.update(
+            deps.storage,
+            ((&offer_asset).into(), (&ask_asset).into()),
+            |last_id| -> StdResult<_> { Ok(last_id.unwrap_or(0).max(path_id)) },
+        )?;
+    }
+
+    // Start the pool ids after the highest registered pool
+    if !LAST_POOL_ID.exists(deps.storage) {
+        let last_pool_id = POOLS
+            .keys(deps.storage, None, None, Order::Descending)
+            .next()
+            .transpose()?
+            .unwrap_or(0);
+        LAST_POOL_ID.save(deps.storage, &last_pool_id)?;
+    }
+
     Ok(Response::default())
 }
+
+#[cfg(feature = "osmosis")]
+#[cfg(test)]
+mod unit_tests {
+    use apollo_cw_asset::{Asset, AssetInfo, AssetInfoUnchecked};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        coin, coins, from_json, to_json_binary, Addr, BankMsg, ContractResult, CosmosMsg, Decimal,
+        Deps, DepsMut, Reply, SubMsgResponse, SubMsgResult, SystemResult, Uint128, WasmMsg,
+    };
+    use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration};
+    use cw_dex::osmosis::OsmosisPool;
+    use cw_dex::Pool;
+
+    use crate::contract::{
+        accept_admin, add_path_manager, assert_minimum_receive, basket_liquidate, block_pool,
+        clear_proposal, execute, execute_swap_operations, migrate, minimum_receive_with_max_spread,
+        propose_new_admin, query_admin, query_all_paths, query_asset_list,
+        query_best_path_for_pair, query_blocked_pools, query_path_managers, query_paths_for_pair,
+        query_pause_info, query_simulate_paths_for_pair, query_supported_ask_assets,
+        query_supported_offer_assets, refund_leftover, remove_path, remove_path_manager,
+        renounce_admin, replace_path, reply, set_guardian, set_path, set_paused, unblock_pool,
+        update_asset_list, update_config, SWAP_OPERATION_REPLY_ID,
+    };
+    use crate::msg::{
+        CallbackMsg, Cw20HookMsg, ExecuteMsg, MigrateMsg, PauseInfoResponse, SwapResponse,
+    };
+    use crate::operations::{SwapOperation, SwapOperationsList, SwapOperationsListUnchecked};
+    use crate::state::{
+        Config, HopPenalty, PendingSwap, ADMIN, ALLOWED_ASSETS, CONFIG, DENIED_ASSETS, PATHS,
+        PENDING_SWAP,
+    };
+    use crate::ContractError;
+    use cw_controllers::AdminError;
+
+    fn operations(offer_asset_info: AssetInfo) -> SwapOperationsList {
+        SwapOperationsList::new(vec![SwapOperation::new(
+            Pool::Osmosis(OsmosisPool::unchecked(1)),
+            offer_asset_info,
+            AssetInfo::Native("uask".to_string()),
+        )])
+    }
+
+    fn save_config(deps: DepsMut) {
+        CONFIG
+            .save(
+                deps.storage,
+                &Config {
+                    fee_bps: 0,
+                    fee_recipient: Addr::unchecked("fee_recipient"),
+                    default_hop_penalty: None,
+                    allowlist_enabled: false,
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn cw20_offer_amount_is_pulled_with_transfer_from() {
+        let mut deps = mock_dependencies();
+        save_config(deps.as_mut());
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&BalanceResponse {
+                    balance: Uint128::zero(),
+                })
+                .unwrap(),
+            ))
+        });
+        let env = mock_env();
+        let info = mock_info("user", &[]);
+
+        let res = execute_swap_operations(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            info.sender.clone(),
+            operations(AssetInfo::Cw20(Addr::unchecked("token"))),
+            Some(Uint128::new(100)),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(res.messages.len(), 4);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "user".to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        match &res.messages[1].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_json(msg).unwrap() {
+                ExecuteMsg::Callback(CallbackMsg::ExecuteSwapOperations {
+                    offer_amount, ..
+                }) => assert_eq!(offer_amount, Uint128::new(100)),
+                other => panic!("unexpected message: {:?}", other),
+            },
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cw20_offer_requires_offer_amount() {
+        let mut deps = mock_dependencies();
+        save_config(deps.as_mut());
+        let info = mock_info("user", &[]);
+
+        let err = execute_swap_operations(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            info.sender,
+            operations(AssetInfo::Cw20(Addr::unchecked("token"))),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, ContractError::MustProvideOfferAmount));
+    }
+
+    #[test]
+    fn native_funds_must_match_offer_amount() {
+        let mut deps = mock_dependencies();
+        save_config(deps.as_mut());
+        let info = mock_info("user", &coins(150, "uoffer"));
+
+        let err = execute_swap_operations(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            info.sender.clone(),
+            operations(AssetInfo::Native("uoffer".to_string())),
+            Some(Uint128::new(100)),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Std(_)));
+
+        let res = execute_swap_operations(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            info.sender,
+            operations(AssetInfo::Native("uoffer".to_string())),
+            Some(Uint128::new(150)),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 3);
+    }
+
+    #[test]
+    fn funds_other_than_offer_asset_are_rejected() {
+        let mut deps = mock_dependencies();
+        save_config(deps.as_mut());
+        let info = mock_info("user", &[coin(100, "uoffer"), coin(1, "uother")]);
+
+        let err = execute_swap_operations(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            info.sender,
+            operations(AssetInfo::Native("uoffer".to_string())),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, ContractError::UnexpectedFunds { .. }));
+    }
+
+    #[test]
+    fn basket_liquidate_rejects_funds_other_than_offer_assets() {
+        let mut deps = mock_dependencies();
+        save_config(deps.as_mut());
+        let info = mock_info("user", &[coin(100, "uoffer"), coin(1, "uother")]);
+
+        let err = basket_liquidate(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            vec![Asset::new(AssetInfo::Native("uoffer".to_string()), 100u128)].into(),
+            AssetInfo::Native("uask".to_string()),
+            None,
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, ContractError::UnexpectedFunds { .. }));
+    }
+
+    #[test]
+    fn swap_operation_reply_waits_for_every_swap_message() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        PENDING_SWAP
+            .save(
+                deps.as_mut().storage,
+                &PendingSwap {
+                    hop: 1,
+                    pending_msgs: 2,
+                    operations: None,
+                    ask_asset_info: native("uask"),
+                    prev_balance: Uint128::zero(),
+                    to: Addr::unchecked("user"),
+                },
+            )
+            .unwrap();
+        let swap_reply = || Reply {
+            id: SWAP_OPERATION_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+
+        // The first swap message does not finish the operation
+        let res = reply(deps.as_mut(), env.clone(), swap_reply()).unwrap();
+        assert!(res.messages.is_empty());
+        assert_eq!(
+            PENDING_SWAP
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .pending_msgs,
+            1
+        );
+
+        // The last one sends the returned amount to the recipient
+        deps.querier
+            .update_balance(env.contract.address.clone(), coins(50, "uask"));
+        let res = reply(deps.as_mut(), env, swap_reply()).unwrap();
+        assert_eq!(
+            res.messages
+                .iter()
+                .map(|msg| msg.msg.clone())
+                .collect::<Vec<_>>(),
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: coins(50, "uask"),
+            })]
+        );
+        assert!(PENDING_SWAP
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn failed_swap_reports_its_hop() {
+        let mut deps = mock_dependencies();
+        PENDING_SWAP
+            .save(
+                deps.as_mut().storage,
+                &PendingSwap {
+                    hop: 1,
+                    pending_msgs: 1,
+                    operations: None,
+                    ask_asset_info: native("uask"),
+                    prev_balance: Uint128::zero(),
+                    to: Addr::unchecked("user"),
+                },
+            )
+            .unwrap();
+
+        let err = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: SWAP_OPERATION_REPLY_ID,
+                result: SubMsgResult::Err("pool error".to_string()),
+            },
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ContractError::SwapOperationFailed { hop: 1, error } if error == "pool error"
+        ));
+    }
+
+    #[test]
+    fn leftover_balance_is_refunded() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        deps.querier.update_balance(
+            env.contract.address.clone(),
+            vec![coin(120, "uoffer"), coin(5, "uintermediate")],
+        );
+
+        let res = refund_leftover(
+            deps.as_mut(),
+            env,
+            vec![
+                Asset::new(AssetInfo::Native("uoffer".to_string()), 100u128),
+                Asset::new(AssetInfo::Native("uintermediate".to_string()), 5u128),
+            ]
+            .into(),
+            Addr::unchecked("user"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages
+                .iter()
+                .map(|msg| msg.msg.clone())
+                .collect::<Vec<_>>(),
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: coins(20, "uoffer"),
+            })]
+        );
+    }
+
+    #[test]
+    fn expired_deadline_is_rejected() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(100, "uoffer")),
+            ExecuteMsg::ExecuteSwapOperations {
+                operations: SwapOperationsListUnchecked::from(operations(AssetInfo::Native(
+                    "uoffer".to_string(),
+                ))),
+                offer_amount: None,
+                minimum_receive: None,
+                max_spread: None,
+                belief_price: None,
+                to: None,
+                deadline: Some(Expiration::AtHeight(env.block.height - 1)),
+            },
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, ContractError::DeadlineExpired { .. }));
+    }
+
+    #[test]
+    fn swap_result_is_returned_as_data() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_balance("recipient", coins(150, "uask"));
+
+        let res = assert_minimum_receive(
+            deps.as_mut(),
+            AssetInfo::Native("uask".to_string()),
+            Uint128::new(100),
+            vec![Asset::new(AssetInfo::Native("uoffer".to_string()), 10u128)].into(),
+            Some(Uint128::new(50)),
+            Addr::unchecked("recipient"),
+        )
+        .unwrap();
+
+        let data: SwapResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(data.return_amount, Uint128::new(50));
+    }
+
+    #[test]
+    fn failing_path_simulation_is_reported() {
+        let mut deps = mock_dependencies();
+        let offer_asset = AssetInfo::Native("uoffer".to_string());
+        let ask_asset = AssetInfo::Native("uask".to_string());
+        PATHS
+            .save(
+                deps.as_mut().storage,
+                ((&offer_asset).into(), (&ask_asset).into(), 1),
+                &operations(offer_asset.clone()),
+            )
+            .unwrap();
+
+        // The mock querier does not support pool queries, so the simulation
+        // fails
+        let simulations =
+            query_simulate_paths_for_pair(deps.as_ref(), Uint128::new(100), offer_asset, ask_asset)
+                .unwrap();
+
+        assert_eq!(simulations.len(), 1);
+        assert_eq!(simulations[0].path_id, 1);
+        assert_eq!(simulations[0].return_amount, None);
+        assert_eq!(simulations[0].net_amount, None);
+        assert!(simulations[0].error.is_some());
+    }
+
+    #[test]
+    fn best_path_fails_only_if_every_path_fails() {
+        let mut deps = mock_dependencies();
+        save_config(deps.as_mut());
+        let offer_asset = AssetInfo::Native("uoffer".to_string());
+        let ask_asset = AssetInfo::Native("uask".to_string());
+        PATHS
+            .save(
+                deps.as_mut().storage,
+                ((&offer_asset).into(), (&ask_asset).into(), 1),
+                &operations(offer_asset.clone()),
+            )
+            .unwrap();
+
+        let err = query_best_path_for_pair(
+            deps.as_ref(),
+            Uint128::new(100),
+            offer_asset,
+            ask_asset,
+            None,
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, ContractError::NoPathFound { .. }));
+    }
+
+    fn native(denom: &str) -> AssetInfo {
+        AssetInfo::Native(denom.to_string())
+    }
+
+    fn path(offer_asset: &str, ask_asset: &str, pool_id: u64) -> SwapOperationsList {
+        SwapOperationsList::new(vec![SwapOperation::new(
+            Pool::Osmosis(OsmosisPool::unchecked(pool_id)),
+            native(offer_asset),
+            native(ask_asset),
+        )])
+    }
+
+    #[test]
+    fn supported_assets_are_paginated_and_deduplicated() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+        let info = mock_info("admin", &[]);
+
+        // Two paths for uatom -> uosmo, and one path from uatom to each of
+        // uion and ustars
+        for (ask_asset, pool_id) in [("uosmo", 1), ("uosmo", 2), ("uion", 3), ("ustars", 4)] {
+            set_path(
+                deps.as_mut(),
+                info.clone(),
+                native("uatom"),
+                native(ask_asset),
+                path("uatom", ask_asset, pool_id),
+                true,
+            )
+            .unwrap();
+        }
+
+        let ask_assets =
+            query_supported_ask_assets(deps.as_ref(), native("uatom"), None, Some(2)).unwrap();
+        assert_eq!(ask_assets, vec![native("uion"), native("uosmo")]);
+        let ask_assets = query_supported_ask_assets(
+            deps.as_ref(),
+            native("uatom"),
+            Some(native("uosmo")),
+            Some(2),
+        )
+        .unwrap();
+        assert_eq!(ask_assets, vec![native("ustars")]);
+
+        let offer_assets =
+            query_supported_offer_assets(deps.as_ref(), native("uatom"), None, None).unwrap();
+        assert_eq!(
+            offer_assets,
+            vec![native("uion"), native("uosmo"), native("ustars")]
+        );
+        let offer_assets =
+            query_supported_offer_assets(deps.as_ref(), native("uosmo"), None, None).unwrap();
+        assert_eq!(offer_assets, vec![native("uatom")]);
+
+        // The pair is only removed once its last path is removed
+        for path_id in [1, 2] {
+            remove_path(
+                deps.as_mut(),
+                info.clone(),
+                native("uatom"),
+                native("uosmo"),
+                path_id,
+                true,
+            )
+            .unwrap();
+            let offer_assets =
+                query_supported_offer_assets(deps.as_ref(), native("uatom"), None, None).unwrap();
+            assert_eq!(offer_assets.contains(&native("uosmo")), path_id == 1);
+        }
+    }
+
+    #[test]
+    fn all_paths_are_paginated() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+
+        for (offer_asset, ask_asset, pool_id) in [("uatom", "uosmo", 1), ("uatom", "uosmo", 2)] {
+            set_path(
+                deps.as_mut(),
+                mock_info("admin", &[]),
+                native(offer_asset),
+                native(ask_asset),
+                path(offer_asset, ask_asset, pool_id),
+                true,
+            )
+            .unwrap();
+        }
+
+        let page = query_all_paths(deps.as_ref(), None, Some(3)).unwrap();
+        assert_eq!(
+            page.iter()
+                .map(|(offer_asset, ask_asset, path_id, _)| (
+                    offer_asset.clone(),
+                    ask_asset.clone(),
+                    *path_id
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (native("uatom"), native("uosmo"), 1),
+                (native("uatom"), native("uosmo"), 2),
+                (native("uosmo"), native("uatom"), 1),
+            ]
+        );
+        assert_eq!(page[1].3, path("uatom", "uosmo", 2));
+
+        let (offer_asset, ask_asset, path_id, _) = page.last().unwrap().clone();
+        let page =
+            query_all_paths(deps.as_ref(), Some((offer_asset, ask_asset, path_id)), None).unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].2, 2);
+    }
+
+    #[test]
+    fn guarded_paths_can_not_be_stored() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+        let info = mock_info("admin", &[]);
+        let guarded_path = SwapOperationsList::new(vec![SwapOperation {
+            min_out: Some(Uint128::new(100)),
+            ..SwapOperation::new(
+                Pool::Osmosis(OsmosisPool::unchecked(1)),
+                native("uatom"),
+                native("uosmo"),
+            )
+        }]);
+
+        let err = set_path(
+            deps.as_mut(),
+            info.clone(),
+            native("uatom"),
+            native("uosmo"),
+            guarded_path.clone(),
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidSwapOperations { .. }));
+
+        set_path(
+            deps.as_mut(),
+            info.clone(),
+            native("uatom"),
+            native("uosmo"),
+            path("uatom", "uosmo", 1),
+            false,
+        )
+        .unwrap();
+        let err = replace_path(
+            deps.as_mut(),
+            info,
+            native("uatom"),
+            native("uosmo"),
+            1,
+            guarded_path,
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidSwapOperations { .. }));
+        assert_eq!(
+            query_paths_for_pair(deps.as_ref(), native("uatom"), native("uosmo")).unwrap(),
+            vec![(1, path("uatom", "uosmo", 1))]
+        );
+    }
+
+    #[test]
+    fn path_ids_are_not_reused() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+        // A path stored before the path id counter existed
+        PATHS
+            .save(
+                deps.as_mut().storage,
+                ((&native("uatom")).into(), (&native("uosmo")).into(), 5),
+                &path("uatom", "uosmo", 1),
+            )
+            .unwrap();
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        fn set_uatom_uosmo_path(deps: DepsMut, pool_id: u64) {
+            set_path(
+                deps,
+                mock_info("admin", &[]),
+                native("uatom"),
+                native("uosmo"),
+                path("uatom", "uosmo", pool_id),
+                false,
+            )
+            .unwrap();
+        }
+        fn path_ids(deps: Deps) -> Vec<u64> {
+            query_paths_for_pair(deps, native("uatom"), native("uosmo"))
+                .unwrap()
+                .into_iter()
+                .map(|(path_id, _)| path_id)
+                .collect()
+        }
+
+        set_uatom_uosmo_path(deps.as_mut(), 2);
+        assert_eq!(path_ids(deps.as_ref()), vec![5, 6]);
+
+        // Removing the path with the highest id does not free the id
+        remove_path(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            native("uatom"),
+            native("uosmo"),
+            6,
+            false,
+        )
+        .unwrap();
+        set_uatom_uosmo_path(deps.as_mut(), 3);
+        assert_eq!(path_ids(deps.as_ref()), vec![5, 7]);
+    }
+
+    #[test]
+    fn admin_is_transferred_once_accepted() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+
+        let err = propose_new_admin(
+            deps.as_mut(),
+            mock_info("other", &[]),
+            "new_admin".to_string(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::AdminError(AdminError::NotAdmin {})
+        ));
+        let err = accept_admin(deps.as_mut(), mock_info("new_admin", &[])).unwrap_err();
+        assert!(matches!(err, ContractError::NoAdminProposal));
+
+        propose_new_admin(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            "new_admin".to_string(),
+        )
+        .unwrap();
+        let res = query_admin(deps.as_ref()).unwrap();
+        assert_eq!(res.admin, Some(Addr::unchecked("admin")));
+        assert_eq!(res.pending_admin, Some(Addr::unchecked("new_admin")));
+
+        let err = accept_admin(deps.as_mut(), mock_info("other", &[])).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+
+        accept_admin(deps.as_mut(), mock_info("new_admin", &[])).unwrap();
+        let res = query_admin(deps.as_ref()).unwrap();
+        assert_eq!(res.admin, Some(Addr::unchecked("new_admin")));
+        assert_eq!(res.pending_admin, None);
+    }
+
+    #[test]
+    fn admin_proposal_can_be_cleared() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+
+        propose_new_admin(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            "new_admin".to_string(),
+        )
+        .unwrap();
+        clear_proposal(deps.as_mut(), mock_info("admin", &[])).unwrap();
+
+        assert_eq!(query_admin(deps.as_ref()).unwrap().pending_admin, None);
+        let err = accept_admin(deps.as_mut(), mock_info("new_admin", &[])).unwrap_err();
+        assert!(matches!(err, ContractError::NoAdminProposal));
+    }
+
+    #[test]
+    fn renounced_admin_cannot_act() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+
+        propose_new_admin(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            "new_admin".to_string(),
+        )
+        .unwrap();
+        add_path_manager(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            "manager".to_string(),
+        )
+        .unwrap();
+        set_guardian(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            Some("guardian".to_string()),
+        )
+        .unwrap();
+        renounce_admin(deps.as_mut(), mock_info("admin", &[])).unwrap();
+
+        let res = query_admin(deps.as_ref()).unwrap();
+        assert_eq!(res.admin, None);
+        assert_eq!(res.pending_admin, None);
+        // The roles appointed by the admin are revoked with it
+        assert!(query_path_managers(deps.as_ref(), None, None)
+            .unwrap()
+            .is_empty());
+        assert_eq!(query_pause_info(deps.as_ref()).unwrap().guardian, None);
+        assert!(propose_new_admin(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            "new_admin".to_string()
+        )
+        .is_err());
+        assert!(accept_admin(deps.as_mut(), mock_info("new_admin", &[])).is_err());
+    }
+
+    #[test]
+    fn path_managers_can_manage_paths_only() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+        let manager = mock_info("manager", &[]);
+
+        let err = set_path(
+            deps.as_mut(),
+            manager.clone(),
+            native("uatom"),
+            native("uosmo"),
+            path("uatom", "uosmo", 1),
+            true,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+        assert!(add_path_manager(deps.as_mut(), manager.clone(), "manager".to_string()).is_err());
+
+        add_path_manager(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            "manager".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            query_path_managers(deps.as_ref(), None, None).unwrap(),
+            vec![Addr::unchecked("manager")]
+        );
+
+        set_path(
+            deps.as_mut(),
+            manager.clone(),
+            native("uatom"),
+            native("uosmo"),
+            path("uatom", "uosmo", 1),
+            true,
+        )
+        .unwrap();
+        remove_path(
+            deps.as_mut(),
+            manager.clone(),
+            native("uatom"),
+            native("uosmo"),
+            1,
+            true,
+        )
+        .unwrap();
+
+        // Config and admin changes stay with the admin
+        let err = update_config(
+            deps.as_mut(),
+            manager.clone(),
+            Some(10),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::AdminError(AdminError::NotAdmin {})
+        ));
+        assert!(propose_new_admin(deps.as_mut(), manager.clone(), "manager".to_string()).is_err());
+
+        remove_path_manager(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            "manager".to_string(),
+        )
+        .unwrap();
+        assert!(query_path_managers(deps.as_ref(), None, None)
+            .unwrap()
+            .is_empty());
+        let err = remove_path_manager(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            "manager".to_string(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotPathManager { .. }));
+        let err = set_path(
+            deps.as_mut(),
+            manager,
+            native("uatom"),
+            native("uosmo"),
+            path("uatom", "uosmo", 1),
+            true,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+    }
+
+    #[test]
+    fn pause_blocks_swaps_only() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+        let swap_msg = ExecuteMsg::ExecuteSwapOperations {
+            operations: SwapOperationsListUnchecked::from(operations(native("uoffer"))),
+            offer_amount: None,
+            minimum_receive: None,
+            max_spread: None,
+            belief_price: None,
+            to: None,
+            deadline: None,
+        };
+
+        let err = set_paused(deps.as_mut(), mock_info("guardian", &[]), true).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+        set_guardian(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            Some("guardian".to_string()),
+        )
+        .unwrap();
+        set_paused(deps.as_mut(), mock_info("guardian", &[]), true).unwrap();
+        assert_eq!(
+            query_pause_info(deps.as_ref()).unwrap(),
+            PauseInfoResponse {
+                paused: true,
+                guardian: Some(Addr::unchecked("guardian")),
+            }
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(100, "uoffer")),
+            swap_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Paused));
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("token", &[]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "user".to_string(),
+                amount: Uint128::new(100),
+                msg: to_json_binary(&Cw20HookMsg::ExecuteSwapOperations {
+                    operations: SwapOperationsListUnchecked::from(operations(AssetInfo::Cw20(
+                        Addr::unchecked("token"),
+                    ))),
+                    minimum_receive: None,
+                    max_spread: None,
+                    belief_price: None,
+                    to: None,
+                    deadline: None,
+                })
+                .unwrap(),
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Paused));
+
+        // Admin messages still work while paused
+        set_path(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            native("uatom"),
+            native("uosmo"),
+            path("uatom", "uosmo", 1),
+            false,
+        )
+        .unwrap();
+
+        set_paused(deps.as_mut(), mock_info("admin", &[]), false).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env,
+            mock_info("user", &coins(100, "uoffer")),
+            swap_msg,
+        )
+        .unwrap_err();
+        assert!(!matches!(err, ContractError::Paused));
+    }
+
+    #[test]
+    fn blocked_pools_are_rejected_and_skipped() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+        set_path(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            native("uatom"),
+            native("uosmo"),
+            path("uatom", "uosmo", 1),
+            false,
+        )
+        .unwrap();
+        let pool = Pool::Osmosis(OsmosisPool::unchecked(1));
+
+        let err = block_pool(deps.as_mut(), mock_info("user", &[]), pool.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized));
+        block_pool(deps.as_mut(), mock_info("admin", &[]), pool.clone()).unwrap();
+
+        let err = SwapOperationsListUnchecked::from(path("uatom", "uosmo", 1))
+            .check(deps.as_ref())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::PoolBlocked { pool: x } if x == pool));
+
+        let simulations = query_simulate_paths_for_pair(
+            deps.as_ref(),
+            Uint128::new(100),
+            native("uatom"),
+            native("uosmo"),
+        )
+        .unwrap();
+        assert_eq!(
+            simulations[0].error,
+            Some(ContractError::PoolBlocked { pool: pool.clone() }.to_string())
+        );
+        // A pair whose only path is blocked is not supported
+        assert!(
+            query_supported_ask_assets(deps.as_ref(), native("uatom"), None, None)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            query_supported_offer_assets(deps.as_ref(), native("uosmo"), None, None)
+                .unwrap()
+                .is_empty()
+        );
+
+        // The blocked pools are paginated
+        let other_pool = Pool::Osmosis(OsmosisPool::unchecked(2));
+        block_pool(deps.as_mut(), mock_info("admin", &[]), other_pool.clone()).unwrap();
+        let first = query_blocked_pools(deps.as_ref(), None, Some(1)).unwrap();
+        assert_eq!(first.len(), 1);
+        let rest = query_blocked_pools(deps.as_ref(), first.first().cloned(), None).unwrap();
+        assert_eq!(
+            [first, rest].concat(),
+            vec![pool.clone(), other_pool.clone()]
+        );
+
+        unblock_pool(deps.as_mut(), mock_info("admin", &[]), pool).unwrap();
+        unblock_pool(deps.as_mut(), mock_info("admin", &[]), other_pool).unwrap();
+        assert!(query_blocked_pools(deps.as_ref(), None, None)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            query_supported_ask_assets(deps.as_ref(), native("uatom"), None, None).unwrap(),
+            vec![native("uosmo")]
+        );
+    }
+
+    #[test]
+    fn asset_lists_restrict_paths_and_supported_assets() {
+        let mut deps = mock_dependencies();
+        save_config(deps.as_mut());
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+        let info = mock_info("admin", &[]);
+        for (ask_asset, pool_id) in [("uosmo", 1), ("uion", 2)] {
+            set_path(
+                deps.as_mut(),
+                info.clone(),
+                native("uatom"),
+                native(ask_asset),
+                path("uatom", ask_asset, pool_id),
+                true,
+            )
+            .unwrap();
+        }
+        let uion: AssetInfoUnchecked = native("uion").into();
+
+        let err = update_asset_list(
+            deps.as_mut(),
+            mock_info("user", &[]),
+            DENIED_ASSETS,
+            "denied",
+            vec![uion.clone()],
+            vec![],
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::AdminError(AdminError::NotAdmin {})
+        ));
+
+        // A denied asset is hidden from the supported assets and can not be
+        // used in new paths
+        update_asset_list(
+            deps.as_mut(),
+            info.clone(),
+            DENIED_ASSETS,
+            "denied",
+            vec![uion.clone()],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            query_asset_list(deps.as_ref(), DENIED_ASSETS, None, None).unwrap(),
+            vec![native("uion")]
+        );
+        assert_eq!(
+            query_supported_ask_assets(deps.as_ref(), native("uatom"), None, None).unwrap(),
+            vec![native("uosmo")]
+        );
+        assert!(
+            query_supported_offer_assets(deps.as_ref(), native("uion"), None, None)
+                .unwrap()
+                .is_empty()
+        );
+        let err = set_path(
+            deps.as_mut(),
+            info.clone(),
+            native("uatom"),
+            native("uion"),
+            path("uatom", "uion", 3),
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::AssetNotAllowed { .. }));
+        let err = query_best_path_for_pair(
+            deps.as_ref(),
+            Uint128::new(100),
+            native("uatom"),
+            native("uion"),
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoPathFound { .. }));
+
+        // The allowlist is only enforced once enabled in the config
+        update_asset_list(
+            deps.as_mut(),
+            info.clone(),
+            DENIED_ASSETS,
+            "denied",
+            vec![],
+            vec![uion.clone()],
+        )
+        .unwrap();
+        let allowed: Vec<AssetInfoUnchecked> = vec![native("uatom").into(), uion];
+        update_asset_list(
+            deps.as_mut(),
+            info.clone(),
+            ALLOWED_ASSETS,
+            "allowed",
+            allowed.clone(),
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            query_supported_ask_assets(deps.as_ref(), native("uatom"), None, None).unwrap(),
+            vec![native("uion"), native("uosmo")]
+        );
+        update_config(
+            deps.as_mut(),
+            info.clone(),
+            None,
+            None,
+            None,
+            None,
+            Some(true),
+        )
+        .unwrap();
+        assert_eq!(
+            query_supported_ask_assets(deps.as_ref(), native("uatom"), None, None).unwrap(),
+            vec![native("uion")]
+        );
+        assert_eq!(
+            query_supported_offer_assets(deps.as_ref(), native("uion"), None, None).unwrap(),
+            vec![native("uatom")]
+        );
+
+        // An empty allowlist denies all assets while enabled
+        update_asset_list(
+            deps.as_mut(),
+            info.clone(),
+            ALLOWED_ASSETS,
+            "allowed",
+            vec![],
+            allowed,
+        )
+        .unwrap();
+        assert!(
+            query_supported_ask_assets(deps.as_ref(), native("uatom"), None, None)
+                .unwrap()
+                .is_empty()
+        );
+        let err = set_path(
+            deps.as_mut(),
+            info,
+            native("uatom"),
+            native("uion"),
+            path("uatom", "uion", 3),
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::AssetNotAllowed { .. }));
+    }
+
+    #[test]
+    fn max_spread_is_applied_to_the_belief_price_return() {
+        // 100 offered at a price of 2 per ask token is expected to return 50
+        let belief_price = Some(Decimal::from_ratio(2u128, 1u128));
+        let max_spread = Some(Decimal::percent(10));
+
+        let minimum =
+            minimum_receive_with_max_spread(Uint128::new(100), None, max_spread, belief_price)
+                .unwrap();
+        assert_eq!(minimum, Some(Uint128::new(45)));
+
+        // The higher of the two minimums is enforced
+        let minimum = minimum_receive_with_max_spread(
+            Uint128::new(100),
+            Some(Uint128::new(48)),
+            max_spread,
+            belief_price,
+        )
+        .unwrap();
+        assert_eq!(minimum, Some(Uint128::new(48)));
+
+        let err =
+            minimum_receive_with_max_spread(Uint128::new(100), None, max_spread, None).unwrap_err();
+        assert!(matches!(err, ContractError::MaxSpreadWithoutBeliefPrice));
+
+        let err = minimum_receive_with_max_spread(
+            Uint128::new(100),
+            None,
+            max_spread,
+            Some(Decimal::zero()),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidBeliefPrice { .. }));
+    }
+
+    #[test]
+    fn default_hop_penalty_can_not_be_set_and_cleared() {
+        let mut deps = mock_dependencies();
+        save_config(deps.as_mut());
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+
+        let err = update_config(
+            deps.as_mut(),
+            mock_info("admin", &[]),
+            None,
+            None,
+            Some(HopPenalty::Bps(100)),
+            Some(true),
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::HopPenaltySetAndCleared));
+    }
+
+    #[test]
+    fn admin_can_not_be_renounced_while_paused() {
+        let mut deps = mock_dependencies();
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+        set_paused(deps.as_mut(), mock_info("admin", &[]), true).unwrap();
+
+        let err = renounce_admin(deps.as_mut(), mock_info("admin", &[])).unwrap_err();
+        assert!(matches!(err, ContractError::RenounceWhilePaused));
+        assert_eq!(
+            query_admin(deps.as_ref()).unwrap().admin,
+            Some(Addr::unchecked("admin"))
+        );
+
+        // Once unpaused the admin can be renounced
+        set_paused(deps.as_mut(), mock_info("admin", &[]), false).unwrap();
+        renounce_admin(deps.as_mut(), mock_info("admin", &[])).unwrap();
+        assert_eq!(query_admin(deps.as_ref()).unwrap().admin, None);
+        assert!(!query_pause_info(deps.as_ref()).unwrap().paused);
+    }
+}
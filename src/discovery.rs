@@ -0,0 +1,216 @@
+use apollo_cw_asset::AssetInfo;
+
+use crate::operations::{SwapOperation, SwapOperationsList};
+use crate::state::RegisteredPool;
+
+/// The maximum number of hops allowed when searching for paths over the
+/// registered pools.
+pub const MAX_DISCOVERY_HOPS: u32 = 3;
+
+/// The maximum number of pools that can be registered, which bounds the gas
+/// used to search the pool graph.
+pub const MAX_REGISTERED_POOLS: usize = 50;
+
+/// The maximum number of routes that are simulated by `DiscoverPaths`. The
+/// search stops once this many routes are found.
+pub const MAX_SIMULATED_ROUTES: usize = 20;
+
+/// Returns up to `limit` paths from `offer_asset` to `ask_asset` over `pools`
+/// with at most `max_hops` operations, for which `is_usable` returns true.
+/// Paths are searched by increasing number of hops, so shorter paths are
+/// found first and the search stops as soon as `limit` paths are found. A
+/// path never visits the same asset or swaps through the same pool twice.
+pub fn find_routes(
+    pools: &[RegisteredPool],
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+    max_hops: u32,
+    limit: usize,
+    is_usable: &dyn Fn(&SwapOperationsList) -> bool,
+) -> Vec<SwapOperationsList> {
+    let mut search = RouteSearch {
+        pools,
+        ask_asset,
+        limit,
+        is_usable,
+        routes: vec![],
+    };
+    if offer_asset == ask_asset {
+        return search.routes;
+    }
+
+    // Iterative deepening: each pass only collects the paths with exactly
+    // `hops` operations
+    for hops in 1..=max_hops as usize {
+        if search.routes.len() >= limit {
+            break;
+        }
+        search.search(
+            offer_asset,
+            hops,
+            &mut vec![],
+            &mut vec![offer_asset.clone()],
+        );
+    }
+    search.routes
+}
+
+struct RouteSearch<'a> {
+    pools: &'a [RegisteredPool],
+    ask_asset: &'a AssetInfo,
+    limit: usize,
+    is_usable: &'a dyn Fn(&SwapOperationsList) -> bool,
+    routes: Vec<SwapOperationsList>,
+}
+
+impl RouteSearch<'_> {
+    fn search(
+        &mut self,
+        current: &AssetInfo,
+        hops: usize,
+        path: &mut Vec<SwapOperation>,
+        visited: &mut Vec<AssetInfo>,
+    ) {
+        for registered in self.pools {
+            if !registered.pool_assets.contains(current)
+                || path.iter().any(|op| op.pool == registered.pool)
+            {
+                continue;
+            }
+
+            for next in registered.pool_assets.iter() {
+                if self.routes.len() >= self.limit {
+                    return;
+                }
+                if visited.contains(next) {
+                    continue;
+                }
+
+                path.push(SwapOperation::new(
+                    registered.pool.clone(),
+                    current.clone(),
+                    next.clone(),
+                ));
+                if path.len() == hops {
+                    // Paths of this length must end in the ask asset
+                    if next == self.ask_asset {
+                        let route = SwapOperationsList::new(path.clone());
+                        if (self.is_usable)(&route) {
+                            self.routes.push(route);
+                        }
+                    }
+                } else if next != self.ask_asset {
+                    visited.push(next.clone());
+                    self.search(next, hops, path, visited);
+                    visited.pop();
+                }
+                path.pop();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "osmosis")]
+#[cfg(test)]
+mod unit_tests {
+    use apollo_cw_asset::AssetInfo;
+    use cw_dex::osmosis::OsmosisPool;
+    use cw_dex::Pool;
+
+    use crate::discovery::{find_routes, MAX_SIMULATED_ROUTES};
+    use crate::operations::{SwapOperation, SwapOperationsList};
+    use crate::state::RegisteredPool;
+
+    fn registered_pool(pool_id: u64, denoms: &[&str]) -> RegisteredPool {
+        RegisteredPool {
+            pool: Pool::Osmosis(OsmosisPool::unchecked(pool_id)),
+            pool_assets: denoms
+                .iter()
+                .map(|denom| AssetInfo::Native(denom.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_find_routes() {
+        let pools = vec![
+            registered_pool(1, &["asset1", "asset2"]),
+            registered_pool(2, &["asset2", "asset3"]),
+            registered_pool(3, &["asset1", "asset3"]),
+            registered_pool(4, &["asset3", "asset4"]),
+        ];
+        let asset1 = AssetInfo::Native("asset1".to_string());
+        let asset2 = AssetInfo::Native("asset2".to_string());
+        let asset3 = AssetInfo::Native("asset3".to_string());
+
+        let routes = find_routes(&pools, &asset1, &asset3, 2, MAX_SIMULATED_ROUTES, &|_| true);
+
+        // The direct route is found before the longer one
+        assert_eq!(
+            routes,
+            vec![
+                SwapOperationsList::new(vec![SwapOperation::new(
+                    Pool::Osmosis(OsmosisPool::unchecked(3)),
+                    asset1.clone(),
+                    asset3.clone(),
+                )]),
+                SwapOperationsList::new(vec![
+                    SwapOperation::new(
+                        Pool::Osmosis(OsmosisPool::unchecked(1)),
+                        asset1.clone(),
+                        asset2.clone(),
+                    ),
+                    SwapOperation::new(
+                        Pool::Osmosis(OsmosisPool::unchecked(2)),
+                        asset2,
+                        asset3.clone(),
+                    ),
+                ]),
+            ]
+        );
+
+        // Only the direct route is found with a single hop
+        assert_eq!(
+            find_routes(&pools, &asset1, &asset3, 1, MAX_SIMULATED_ROUTES, &|_| true).len(),
+            1
+        );
+
+        // Routes rejected by `is_usable` are left out
+        let routes = find_routes(
+            &pools,
+            &asset1,
+            &asset3,
+            2,
+            MAX_SIMULATED_ROUTES,
+            &|route| route.len() > 1,
+        );
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].len(), 2);
+    }
+
+    #[test]
+    fn test_find_routes_stops_at_limit() {
+        // Two hop routes through the first pools, and more direct routes
+        // through the later pools than the limit
+        let mut pools = vec![
+            registered_pool(1, &["asset1", "asset2"]),
+            registered_pool(2, &["asset2", "asset3"]),
+        ];
+        for pool_id in 3..(MAX_SIMULATED_ROUTES as u64 + 8) {
+            pools.push(registered_pool(pool_id, &["asset1", "asset3"]));
+        }
+        let asset1 = AssetInfo::Native("asset1".to_string());
+        let asset3 = AssetInfo::Native("asset3".to_string());
+
+        let routes = find_routes(&pools, &asset1, &asset3, 3, MAX_SIMULATED_ROUTES, &|_| true);
+
+        // The search stops at the limit with only the shortest routes
+        assert_eq!(routes.len(), MAX_SIMULATED_ROUTES);
+        assert!(routes.iter().all(|route| route.len() == 1));
+
+        // Longer routes are found after all shorter ones
+        let routes = find_routes(&pools, &asset1, &asset3, 3, usize::MAX, &|_| true);
+        assert!(routes.len() > MAX_SIMULATED_ROUTES);
+        assert!(routes.windows(2).all(|w| w[0].len() <= w[1].len()));
+    }
+}
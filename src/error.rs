@@ -1,7 +1,8 @@
-use apollo_cw_asset::Asset;
-use cosmwasm_std::{OverflowError, StdError};
+use apollo_cw_asset::{Asset, AssetList};
+use cosmwasm_std::{Coin, Decimal, OverflowError, StdError, Uint128};
+use cw20::Expiration;
 use cw_controllers::AdminError;
-use cw_dex::CwDexError;
+use cw_dex::{CwDexError, Pool};
 use thiserror::Error;
 
 use crate::operations::SwapOperation;
@@ -23,6 +24,36 @@ pub enum ContractError {
     #[error("Incorrect amount of native token sent. You don't need to pass in offer_amount if using native tokens.")]
     IncorrectNativeAmountSent,
 
+    #[error("offer_amount must be provided when swapping a Cw20 token")]
+    MustProvideOfferAmount,
+
+    #[error("Unexpected funds sent: {funds:?}. Only the offer asset {offer_asset} may be sent")]
+    UnexpectedFunds {
+        funds: Vec<Coin>,
+        offer_asset: String,
+    },
+
+    #[error("Swap needs {offer_amount} of the offer asset, which exceeds max_offer {max_offer}")]
+    MaxOfferExceeded {
+        offer_amount: Uint128,
+        max_offer: Uint128,
+    },
+
+    #[error("Not enough liquidity to receive {ask}")]
+    InsufficientLiquidity { ask: Asset },
+
+    #[error("Swap deadline {deadline} has passed")]
+    DeadlineExpired { deadline: Expiration },
+
+    #[error("Invalid max spread {max_spread}, must be at most 1")]
+    InvalidMaxSpread { max_spread: Decimal },
+
+    #[error("max_spread and belief_price must be given together")]
+    MaxSpreadWithoutBeliefPrice,
+
+    #[error("Invalid belief price {belief_price}, must be greater than 0")]
+    InvalidBeliefPrice { belief_price: Decimal },
+
     #[error("Unsupported asset type. Only native and cw20 tokens are supported.")]
     UnsupportedAssetType,
 
@@ -32,6 +63,24 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("No new admin has been proposed")]
+    NoAdminProposal,
+
+    #[error("The router is paused")]
+    Paused,
+
+    #[error("The admin can not be renounced while the router is paused")]
+    RenounceWhilePaused,
+
+    #[error("Pool {pool:?} is blocked")]
+    PoolBlocked { pool: Pool },
+
+    #[error("Asset {asset} is not allowed to be routed")]
+    AssetNotAllowed { asset: String },
+
+    #[error("{address} is not a path manager")]
+    NotPathManager { address: String },
+
     #[error("Invalid swap operations: {operations:?} {reason}")]
     InvalidSwapOperations {
         operations: Vec<SwapOperation>,
@@ -43,13 +92,70 @@ pub enum ContractError {
 
     #[error("Did not receive minimum amount, wanted: {wanted}, got: {got}")]
     FailedMinimumReceive {
-        token_in: Asset,
+        token_in: AssetList,
         wanted: Asset,
         got: Asset,
     },
 
+    #[error("Swap operation {hop} of the path failed: {error}")]
+    SwapOperationFailed { hop: u32, error: String },
+
+    #[error("Swap operation {operation:?} did not return its minimum amount, wanted: {wanted}, got: {got}")]
+    FailedOperationMinimumReceive {
+        operation: SwapOperation,
+        wanted: Uint128,
+        got: Uint128,
+    },
+
+    #[error("Price impact {price_impact} of swap operation {operation:?} exceeds the maximum {max_price_impact}")]
+    MaxPriceImpactExceeded {
+        operation: SwapOperation,
+        price_impact: Decimal,
+        max_price_impact: Decimal,
+    },
+
+    #[error("Price impact of swap operation {operation:?} can not be measured, as swapping {reference_amount} returns nothing")]
+    PriceImpactNotMeasurable {
+        operation: SwapOperation,
+        reference_amount: Uint128,
+    },
+
     #[error("No path found for assets {offer:?} -> {ask:?}")]
     NoPathFound { offer: String, ask: String },
+
+    #[error("Path {path_id} not found for assets {offer:?} -> {ask:?}")]
+    PathNotFound {
+        offer: String,
+        ask: String,
+        path_id: u64,
+    },
+
+    #[error("Invalid split routes: {reason}")]
+    InvalidSplitRoutes { reason: String },
+
+    #[error("Invalid split parameters: {reason}")]
+    InvalidSplitParameters { reason: String },
+
+    #[error("Fee of {fee_bps} bps exceeds the maximum of {max_fee_bps} bps")]
+    InvalidFee { fee_bps: u16, max_fee_bps: u16 },
+
+    #[error("Hop penalty of {bps} bps exceeds the maximum of {max_bps} bps")]
+    InvalidHopPenalty { bps: u16, max_bps: u16 },
+
+    #[error("Can not both set and clear the default hop penalty")]
+    HopPenaltySetAndCleared,
+
+    #[error("Pool is already registered: {pool:?}")]
+    PoolAlreadyRegistered { pool: Pool },
+
+    #[error("Pool {pool_id} is not registered")]
+    PoolNotRegistered { pool_id: u64 },
+
+    #[error("At most {max} pools can be registered")]
+    TooManyPools { max: usize },
+
+    #[error("max_hops must be between 1 and {max}, got {got}")]
+    InvalidMaxHops { max: u32, got: u32 },
 }
 
 impl From<ContractError> for StdError {
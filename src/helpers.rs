@@ -1,15 +1,23 @@
 use std::vec;
 
-use apollo_cw_asset::{Asset, AssetInfo, AssetList};
+use apollo_cw_asset::{Asset, AssetInfo, AssetInfoBase, AssetList};
+use apollo_utils::assets::separate_natives_and_cw20s;
 use cosmwasm_schema::cw_serde;
 
 use cosmwasm_std::{
-    to_json_binary, Addr, Api, Coin, CosmosMsg, Env, MessageInfo, QuerierWrapper, QueryRequest,
-    StdError, StdResult, Uint128, WasmMsg, WasmQuery,
+    to_json_binary, Addr, Api, Coin, CosmosMsg, Decimal, Env, MessageInfo, QuerierWrapper,
+    QueryRequest, StdError, StdResult, Uint128, WasmMsg, WasmQuery,
 };
+use cw20::{Cw20Coin, Cw20ExecuteMsg, Expiration};
+use cw_dex::Pool;
 
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    AdminResponse, BestPathForPairResponse, BestSplitForPairResponse, Cw20HookMsg, DiscoveredPath,
+    ExecuteMsg, InstantiateMsg, PathSimulation, PauseInfoResponse, QueryMsg,
+    SimulateReverseSwapResponse, SimulateSwapResponse,
+};
 use crate::operations::SwapOperationsList;
+use crate::state::{Config, HopPenalty, RegisteredPool};
 
 #[cw_serde]
 pub struct CwDexRouterBase<T>(pub T);
@@ -40,13 +48,14 @@ impl CwDexRouterUnchecked {
 
     pub fn instantiate(
         code_id: u64,
+        msg: &InstantiateMsg,
         admin: Option<String>,
         label: Option<String>,
     ) -> StdResult<CosmosMsg> {
         Ok(CosmosMsg::Wasm(WasmMsg::Instantiate {
             code_id,
             admin,
-            msg: to_json_binary(&InstantiateMsg {})?,
+            msg: to_json_binary(msg)?,
             funds: vec![],
             label: label.unwrap_or_else(|| "cw-dex-router".to_string()),
         }))
@@ -72,12 +81,16 @@ impl CwDexRouter {
         .into())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn execute_swap_operations_msg(
         &self,
         operations: &SwapOperationsList,
         offer_amount: Option<Uint128>,
         minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
         to: Option<String>,
+        deadline: Option<Expiration>,
         funds: Vec<Coin>,
     ) -> StdResult<CosmosMsg> {
         self.call(
@@ -85,61 +98,182 @@ impl CwDexRouter {
                 operations: operations.into(),
                 offer_amount,
                 minimum_receive,
+                max_spread,
+                belief_price,
+                to,
+                deadline,
+            },
+            funds,
+        )
+    }
+
+    /// Returns a message to send a Cw20 `offer_asset` to the router and swap
+    /// it through `operations` with `Cw20HookMsg::ExecuteSwapOperations`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_swap_operations_cw20_msg(
+        &self,
+        offer_asset: &Asset,
+        operations: &SwapOperationsList,
+        minimum_receive: Option<Uint128>,
+        max_spread: Option<Decimal>,
+        belief_price: Option<Decimal>,
+        to: Option<String>,
+        deadline: Option<Expiration>,
+    ) -> StdResult<CosmosMsg> {
+        let addr = match &offer_asset.info {
+            AssetInfo::Cw20(addr) => addr,
+            AssetInfo::Native(_) => {
+                return Err(StdError::generic_err("offer asset must be a Cw20"))
+            }
+        };
+        Ok(WasmMsg::Execute {
+            contract_addr: addr.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: self.addr().to_string(),
+                amount: offer_asset.amount,
+                msg: to_json_binary(&Cw20HookMsg::ExecuteSwapOperations {
+                    operations: operations.into(),
+                    minimum_receive,
+                    max_spread,
+                    belief_price,
+                    to,
+                    deadline,
+                })?,
+            })?,
+            funds: vec![],
+        }
+        .into())
+    }
+
+    pub fn execute_swap_operations_exact_out_msg(
+        &self,
+        operations: &SwapOperationsList,
+        ask_amount: Uint128,
+        max_offer: Uint128,
+        to: Option<String>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::ExecuteSwapOperationsExactOut {
+                operations: operations.into(),
+                ask_amount,
+                max_offer,
                 to,
             },
             funds,
         )
     }
 
+    pub fn execute_split_swap_msg(
+        &self,
+        routes: &[(Uint128, SwapOperationsList)],
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::ExecuteSplitSwap {
+                routes: routes
+                    .iter()
+                    .map(|(amount, operations)| (*amount, operations.into()))
+                    .collect(),
+                minimum_receive,
+                to,
+            },
+            funds,
+        )
+    }
+
+    /// Returns a message to swap `offer_asset` into `ask_asset` through the
+    /// best stored path. Native tokens are sent in the funds field, while Cw20s
+    /// are sent to the router with `Cw20HookMsg::SwapBestPath`.
+    pub fn swap_best_path_msg(
+        &self,
+        offer_asset: &Asset,
+        ask_asset: &AssetInfo,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        exclude_paths: Option<Vec<u64>>,
+    ) -> StdResult<CosmosMsg> {
+        match &offer_asset.info {
+            AssetInfo::Native(_) => self.call(
+                ExecuteMsg::SwapBestPath {
+                    offer_asset: offer_asset.to_owned().into(),
+                    ask_asset: ask_asset.to_owned().into(),
+                    minimum_receive,
+                    to,
+                    exclude_paths,
+                },
+                vec![offer_asset.try_into()?],
+            ),
+            AssetInfo::Cw20(addr) => Ok(WasmMsg::Execute {
+                contract_addr: addr.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: self.addr().to_string(),
+                    amount: offer_asset.amount,
+                    msg: to_json_binary(&Cw20HookMsg::SwapBestPath {
+                        ask_asset: ask_asset.to_owned().into(),
+                        minimum_receive,
+                        to,
+                        exclude_paths,
+                    })?,
+                })?,
+                funds: vec![],
+            }
+            .into()),
+        }
+    }
+
     /// Returns message to call BasketLiquidate, as well as approve spend on any
     /// CW20s in `offer_assets`. Also takes care of sending native tokens in
     /// `offer_assets` to the contract via the funds field.
-    // pub fn basket_liquidate_msgs(
-    //     &self,
-    //     offer_assets: AssetList,
-    //     receive_asset: &AssetInfo,
-    //     minimum_receive: Option<Uint128>,
-    //     to: Option<String>,
-    // ) -> StdResult<Vec<CosmosMsg>> {
-    //     //Extract all native tokens to send in funds field.
-    //     let (funds, _) = separate_natives_and_cw20s(&offer_assets);
-
-    //     //Extract all cw20s and approve allowance to router.
-    //     let mut msgs: Vec<CosmosMsg> = offer_assets
-    //         .into_iter()
-    //         .filter_map(|x| match &x.info {
-    //             AssetInfoBase::Cw20(addr) => Some(Cw20Coin {
-    //                 address: addr.to_string(),
-    //                 amount: x.amount,
-    //             }),
-    //             _ => None,
-    //         })
-    //         .map(|x| {
-    //             Ok(CosmosMsg::Wasm(WasmMsg::Execute {
-    //                 contract_addr: x.address,
-    //                 msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
-    //                     spender: self.addr().to_string(),
-    //                     amount: x.amount,
-    //                     expires: None,
-    //                 })?,
-    //                 funds: vec![],
-    //             }))
-    //         })
-    //         .collect::<StdResult<Vec<_>>>()?;
-
-    //     let swap_msg = self.call(
-    //         ExecuteMsg::BasketLiquidate {
-    //             offer_assets: offer_assets.into(),
-    //             receive_asset: receive_asset.to_owned().into(),
-    //             minimum_receive,
-    //             to,
-    //         },
-    //         funds,
-    //     )?;
-    //     msgs.push(swap_msg);
-
-    //     Ok(msgs)
-    // }
+    pub fn basket_liquidate_msgs(
+        &self,
+        offer_assets: AssetList,
+        receive_asset: &AssetInfo,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        //Extract all native tokens to send in funds field.
+        let (funds, _) = separate_natives_and_cw20s(&offer_assets);
+
+        let swap_msg = self.call(
+            ExecuteMsg::BasketLiquidate {
+                offer_assets: offer_assets.clone().into(),
+                receive_asset: receive_asset.to_owned().into(),
+                minimum_receive,
+                to,
+            },
+            funds,
+        )?;
+
+        //Extract all cw20s and approve allowance to router.
+        let mut msgs: Vec<CosmosMsg> = offer_assets
+            .into_iter()
+            .filter_map(|x| match &x.info {
+                AssetInfoBase::Cw20(addr) => Some(Cw20Coin {
+                    address: addr.to_string(),
+                    amount: x.amount,
+                }),
+                _ => None,
+            })
+            .map(|x| {
+                Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: x.address,
+                    msg: to_json_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: self.addr().to_string(),
+                        amount: x.amount,
+                        expires: None,
+                    })?,
+                    funds: vec![],
+                }))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        msgs.push(swap_msg);
+
+        Ok(msgs)
+    }
 
     pub fn set_path_msg(
         &self,
@@ -159,12 +293,150 @@ impl CwDexRouter {
         )
     }
 
+    pub fn remove_path_msg(
+        &self,
+        offer_asset: AssetInfo,
+        ask_asset: AssetInfo,
+        path_id: u64,
+        bidirectional: bool,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::RemovePath {
+                offer_asset: offer_asset.into(),
+                ask_asset: ask_asset.into(),
+                path_id,
+                bidirectional,
+            },
+            vec![],
+        )
+    }
+
+    pub fn replace_path_msg(
+        &self,
+        offer_asset: AssetInfo,
+        ask_asset: AssetInfo,
+        path_id: u64,
+        path: &SwapOperationsList,
+        bidirectional: bool,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::ReplacePath {
+                offer_asset: offer_asset.into(),
+                ask_asset: ask_asset.into(),
+                path_id,
+                path: path.into(),
+                bidirectional,
+            },
+            vec![],
+        )
+    }
+
+    pub fn register_pool_msg(&self, pool: Pool) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RegisterPool { pool }, vec![])
+    }
+
+    pub fn deregister_pool_msg(&self, pool_id: u64) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::DeregisterPool { pool_id }, vec![])
+    }
+
+    pub fn add_path_manager_msg(&self, address: String) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::AddPathManager { address }, vec![])
+    }
+
+    pub fn remove_path_manager_msg(&self, address: String) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RemovePathManager { address }, vec![])
+    }
+
+    pub fn propose_new_admin_msg(&self, new_admin: String) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ProposeNewAdmin { new_admin }, vec![])
+    }
+
+    pub fn accept_admin_msg(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::AcceptAdmin {}, vec![])
+    }
+
+    pub fn clear_proposal_msg(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::ClearProposal {}, vec![])
+    }
+
+    pub fn renounce_admin_msg(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::RenounceAdmin {}, vec![])
+    }
+
+    pub fn set_guardian_msg(&self, guardian: Option<String>) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::SetGuardian { guardian }, vec![])
+    }
+
+    pub fn pause_msg(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Pause {}, vec![])
+    }
+
+    pub fn unpause_msg(&self) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Unpause {}, vec![])
+    }
+
+    pub fn block_pool_msg(&self, pool: Pool) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::BlockPool { pool }, vec![])
+    }
+
+    pub fn unblock_pool_msg(&self, pool: Pool) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::UnblockPool { pool }, vec![])
+    }
+
+    pub fn update_allowed_assets_msg(
+        &self,
+        add: &[AssetInfo],
+        remove: &[AssetInfo],
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::UpdateAllowedAssets {
+                add: add.iter().map(|x| x.to_owned().into()).collect(),
+                remove: remove.iter().map(|x| x.to_owned().into()).collect(),
+            },
+            vec![],
+        )
+    }
+
+    pub fn update_denied_assets_msg(
+        &self,
+        add: &[AssetInfo],
+        remove: &[AssetInfo],
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::UpdateDeniedAssets {
+                add: add.iter().map(|x| x.to_owned().into()).collect(),
+                remove: remove.iter().map(|x| x.to_owned().into()).collect(),
+            },
+            vec![],
+        )
+    }
+
+    pub fn update_config_msg(
+        &self,
+        fee_bps: Option<u16>,
+        fee_recipient: Option<String>,
+        default_hop_penalty: Option<HopPenalty>,
+        clear_default_hop_penalty: Option<bool>,
+        allowlist_enabled: Option<bool>,
+    ) -> StdResult<CosmosMsg> {
+        self.call(
+            ExecuteMsg::UpdateConfig {
+                fee_bps,
+                fee_recipient,
+                default_hop_penalty,
+                clear_default_hop_penalty,
+                allowlist_enabled,
+            },
+            vec![],
+        )
+    }
+
     pub fn simulate_swap_operations(
         &self,
         querier: &QuerierWrapper,
         offer_amount: Uint128,
         operations: &SwapOperationsList,
-    ) -> StdResult<Uint128> {
+    ) -> StdResult<SimulateSwapResponse> {
         querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: self.0.to_string(),
             msg: to_json_binary(&QueryMsg::SimulateSwapOperations {
@@ -174,27 +446,103 @@ impl CwDexRouter {
         }))
     }
 
-    // pub fn simulate_basket_liquidate(
-    //     &self,
-    //     querier: &QuerierWrapper,
-    //     offer_assets: AssetList,
-    //     receive_asset: &AssetInfo,
-    // ) -> StdResult<Uint128> {
-    //     querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-    //         contract_addr: self.0.to_string(),
-    //         msg: to_binary(&QueryMsg::SimulateBasketLiquidate {
-    //             offer_assets: offer_assets.into(),
-    //             receive_asset: receive_asset.to_owned().into(),
-    //         })?,
-    //     }))
-    // }
+    pub fn simulate_reverse_swap_operations(
+        &self,
+        querier: &QuerierWrapper,
+        ask_amount: Uint128,
+        operations: &SwapOperationsList,
+    ) -> StdResult<SimulateReverseSwapResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::SimulateReverseSwapOperations {
+                ask_amount,
+                operations: operations.into(),
+            })?,
+        }))
+    }
+
+    pub fn simulate_basket_liquidate(
+        &self,
+        querier: &QuerierWrapper,
+        offer_assets: AssetList,
+        receive_asset: &AssetInfo,
+    ) -> StdResult<SimulateSwapResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::SimulateBasketLiquidate {
+                offer_assets: offer_assets.into(),
+                receive_asset: receive_asset.to_owned().into(),
+            })?,
+        }))
+    }
+
+    pub fn simulate_split_swap(
+        &self,
+        querier: &QuerierWrapper,
+        routes: &[(Uint128, SwapOperationsList)],
+    ) -> StdResult<SimulateSwapResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::SimulateSplitSwap {
+                routes: routes
+                    .iter()
+                    .map(|(amount, operations)| (*amount, operations.into()))
+                    .collect(),
+            })?,
+        }))
+    }
+
+    pub fn query_config(&self, querier: &QuerierWrapper) -> StdResult<Config> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::Config {})?,
+        }))
+    }
+
+    pub fn query_admin(&self, querier: &QuerierWrapper) -> StdResult<AdminResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::Admin {})?,
+        }))
+    }
+
+    pub fn query_pause_info(&self, querier: &QuerierWrapper) -> StdResult<PauseInfoResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::PauseInfo {})?,
+        }))
+    }
+
+    pub fn query_blocked_pools(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<Pool>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Pool>> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::BlockedPools { start_after, limit })?,
+        }))
+    }
+
+    pub fn query_path_managers(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Addr>> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::PathManagers { start_after, limit })?,
+        }))
+    }
 
     pub fn query_path_for_pair(
         &self,
         querier: &QuerierWrapper,
         offer_asset: &AssetInfo,
         ask_asset: &AssetInfo,
-    ) -> StdResult<SwapOperationsList> {
+    ) -> StdResult<Vec<(u64, SwapOperationsList)>> {
         querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: self.0.to_string(),
             msg: to_json_binary(&QueryMsg::PathsForPair {
@@ -204,15 +552,139 @@ impl CwDexRouter {
         }))
     }
 
+    pub fn query_best_path_for_pair(
+        &self,
+        querier: &QuerierWrapper,
+        offer_asset: &AssetInfo,
+        offer_amount: Uint128,
+        ask_asset: &AssetInfo,
+        exclude_paths: Option<Vec<u64>>,
+        hop_penalty: Option<HopPenalty>,
+    ) -> StdResult<BestPathForPairResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::BestPathForPair {
+                offer_asset: offer_asset.to_owned().into(),
+                offer_amount,
+                ask_asset: ask_asset.to_owned().into(),
+                exclude_paths,
+                hop_penalty,
+            })?,
+        }))
+    }
+
+    pub fn query_simulate_paths_for_pair(
+        &self,
+        querier: &QuerierWrapper,
+        offer_asset: &AssetInfo,
+        offer_amount: Uint128,
+        ask_asset: &AssetInfo,
+    ) -> StdResult<Vec<PathSimulation>> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::SimulatePathsForPair {
+                offer_asset: offer_asset.to_owned().into(),
+                offer_amount,
+                ask_asset: ask_asset.to_owned().into(),
+            })?,
+        }))
+    }
+
+    pub fn query_best_split_for_pair(
+        &self,
+        querier: &QuerierWrapper,
+        offer_asset: &AssetInfo,
+        offer_amount: Uint128,
+        ask_asset: &AssetInfo,
+        max_splits: Option<u32>,
+        granularity: Option<u32>,
+    ) -> StdResult<BestSplitForPairResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::BestSplitForPair {
+                offer_asset: offer_asset.to_owned().into(),
+                offer_amount,
+                ask_asset: ask_asset.to_owned().into(),
+                max_splits,
+                granularity,
+            })?,
+        }))
+    }
+
+    pub fn query_discover_paths(
+        &self,
+        querier: &QuerierWrapper,
+        offer_asset: &AssetInfo,
+        ask_asset: &AssetInfo,
+        max_hops: u32,
+        offer_amount: Uint128,
+    ) -> StdResult<Vec<DiscoveredPath>> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::DiscoverPaths {
+                offer_asset: offer_asset.to_owned().into(),
+                ask_asset: ask_asset.to_owned().into(),
+                max_hops,
+                offer_amount,
+            })?,
+        }))
+    }
+
+    pub fn query_registered_pools(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<(u64, RegisteredPool)>> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::RegisteredPools { start_after, limit })?,
+        }))
+    }
+
+    pub fn query_allowed_assets(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<&AssetInfo>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<AssetInfo>> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::AllowedAssets {
+                start_after: start_after.map(|x| x.to_owned().into()),
+                limit,
+            })?,
+        }))
+    }
+
+    pub fn query_denied_assets(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<&AssetInfo>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<AssetInfo>> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::DeniedAssets {
+                start_after: start_after.map(|x| x.to_owned().into()),
+                limit,
+            })?,
+        }))
+    }
+
     pub fn query_supported_offer_assets(
         &self,
         querier: &QuerierWrapper,
         ask_asset: &AssetInfo,
+        start_after: Option<&AssetInfo>,
+        limit: Option<u32>,
     ) -> StdResult<Vec<AssetInfo>> {
         querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: self.0.to_string(),
             msg: to_json_binary(&QueryMsg::SupportedOfferAssets {
                 ask_asset: ask_asset.to_owned().into(),
+                start_after: start_after.map(|x| x.to_owned().into()),
+                limit,
             })?,
         }))
     }
@@ -221,11 +693,36 @@ impl CwDexRouter {
         &self,
         querier: &QuerierWrapper,
         offer_asset: &AssetInfo,
+        start_after: Option<&AssetInfo>,
+        limit: Option<u32>,
     ) -> StdResult<Vec<AssetInfo>> {
         querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
             contract_addr: self.0.to_string(),
             msg: to_json_binary(&QueryMsg::SupportedAskAssets {
                 offer_asset: offer_asset.to_owned().into(),
+                start_after: start_after.map(|x| x.to_owned().into()),
+                limit,
+            })?,
+        }))
+    }
+
+    pub fn query_all_paths(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<(&AssetInfo, &AssetInfo, u64)>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<(AssetInfo, AssetInfo, u64, SwapOperationsList)>> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: self.0.to_string(),
+            msg: to_json_binary(&QueryMsg::AllPaths {
+                start_after: start_after.map(|(offer_asset, ask_asset, path_id)| {
+                    (
+                        offer_asset.to_owned().into(),
+                        ask_asset.to_owned().into(),
+                        path_id,
+                    )
+                }),
+                limit,
             })?,
         }))
     }
@@ -1,12 +1,22 @@
-use apollo_cw_asset::{Asset, AssetInfo, AssetInfoUnchecked};
+use apollo_cw_asset::{
+    AssetInfo, AssetInfoUnchecked, AssetList, AssetListUnchecked, AssetUnchecked,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{wasm_execute, Addr, CosmosMsg, Empty, Env, Uint128};
-use cw20::Cw20ReceiveMsg;
+use cosmwasm_std::{wasm_execute, Addr, CosmosMsg, Decimal, Env, Uint128};
+use cw20::{Cw20ReceiveMsg, Expiration};
+use cw_dex::Pool;
 
-use crate::operations::{SwapOperation, SwapOperationsListUnchecked};
+use crate::operations::{SwapOperationsList, SwapOperationsListUnchecked};
+use crate::state::HopPenalty;
 use crate::ContractError;
 
-pub type InstantiateMsg = Empty;
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The protocol fee taken from the output of routed swaps, in basis points.
+    pub fee_bps: u16,
+    /// The address that receives the protocol fee.
+    pub fee_recipient: String,
+}
 
 #[cw_serde]
 pub enum ExecuteMsg {
@@ -17,34 +27,195 @@ pub enum ExecuteMsg {
         /// swap a Cw20 with TransferFrom
         offer_amount: Option<Uint128>,
         minimum_receive: Option<Uint128>,
+        /// Alternative to `minimum_receive`. The maximum share by which the
+        /// received amount may be lower than expected at `belief_price`. If
+        /// both are set, the higher minimum is enforced.
+        max_spread: Option<Decimal>,
+        /// The price of the ask asset in the offer asset that the caller
+        /// expects, i.e. the expected amount received by the recipient after
+        /// the protocol fee is `offer_amount / belief_price`. Required with
+        /// `max_spread`.
+        belief_price: Option<Decimal>,
+        to: Option<String>,
+        /// The swap is rejected once this block height or time has passed.
+        deadline: Option<Expiration>,
+    },
+    /// Swaps `offer_asset` into `ask_asset` using the stored path with the
+    /// highest simulated return at execution time.
+    SwapBestPath {
+        offer_asset: AssetUnchecked,
+        ask_asset: AssetInfoUnchecked,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        exclude_paths: Option<Vec<u64>>,
+    },
+    /// Swaps the smallest amount of the offer asset needed for `to` to
+    /// receive `ask_amount` through `operations`. At most `max_offer` of the
+    /// offer asset is spent. Native offer assets must be sent in full and the
+    /// unspent amount is refunded, Cw20s are pulled with TransferFrom.
+    ExecuteSwapOperationsExactOut {
+        operations: SwapOperationsListUnchecked,
+        ask_amount: Uint128,
+        max_offer: Uint128,
+        to: Option<String>,
+    },
+    /// Splits the offer amount over several paths. Each route swaps its own
+    /// amount of the offer asset, and the combined output of all routes is
+    /// checked against `minimum_receive`. All routes must have the same offer
+    /// and ask assets.
+    ExecuteSplitSwap {
+        routes: Vec<(Uint128, SwapOperationsListUnchecked)>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    },
+    /// Swaps all of `offer_assets` into `receive_asset`, using the best
+    /// stored path for each offer asset. Only the native offer assets may be
+    /// sent, and leftover balances are refunded to the sender.
+    BasketLiquidate {
+        offer_assets: AssetListUnchecked,
+        receive_asset: AssetInfoUnchecked,
+        minimum_receive: Option<Uint128>,
         to: Option<String>,
     },
-    // BasketLiquidate {
-    //     offer_assets: AssetListUnchecked,
-    //     receive_asset: AssetInfoUnchecked,
-    //     minimum_receive: Option<Uint128>,
-    //     to: Option<String>,
-    // },
+    /// Stores `path` for the (offer_asset, ask_asset) pair. Operations of a
+    /// stored path can not have a `min_out` or `max_price_impact`.
     SetPath {
         offer_asset: AssetInfoUnchecked,
         ask_asset: AssetInfoUnchecked,
         path: SwapOperationsListUnchecked,
         bidirectional: bool,
     },
+    /// Removes the path stored under `path_id` for the (offer_asset,
+    /// ask_asset) pair. If `bidirectional` is true, the reversed path is also
+    /// removed from the (ask_asset, offer_asset) pair.
+    RemovePath {
+        offer_asset: AssetInfoUnchecked,
+        ask_asset: AssetInfoUnchecked,
+        path_id: u64,
+        bidirectional: bool,
+    },
+    /// Overwrites the path stored under `path_id` for the (offer_asset,
+    /// ask_asset) pair. If `bidirectional` is true, the reverse of the old
+    /// path is replaced with the reverse of the new one. As with `SetPath`,
+    /// the operations can not have a `min_out` or `max_price_impact`.
+    ReplacePath {
+        offer_asset: AssetInfoUnchecked,
+        ask_asset: AssetInfoUnchecked,
+        path_id: u64,
+        path: SwapOperationsListUnchecked,
+        bidirectional: bool,
+    },
+    /// Adds `address` to the path managers, which may call `SetPath`,
+    /// `RemovePath` and `ReplacePath`. Only callable by the admin.
+    AddPathManager {
+        address: String,
+    },
+    /// Removes `address` from the path managers. Only callable by the admin.
+    RemovePathManager {
+        address: String,
+    },
+    /// Registers a pool to be used for path discovery. The assets of the pool
+    /// are queried and stored together with it. At most
+    /// `MAX_REGISTERED_POOLS` pools can be registered at a time.
+    RegisterPool {
+        pool: Pool,
+    },
+    /// Removes a pool from the set of pools used for path discovery.
+    DeregisterPool {
+        pool_id: u64,
+    },
+    /// Proposes `new_admin` as the new admin. The proposed address becomes
+    /// the admin once it calls `AcceptAdmin`. Only callable by the admin.
+    ProposeNewAdmin {
+        new_admin: String,
+    },
+    /// Accepts the admin role. Only callable by the proposed admin.
+    AcceptAdmin {},
+    /// Removes the pending admin proposal. Only callable by the admin.
+    ClearProposal {},
+    /// Removes the admin, so that all admin-only messages are disabled for
+    /// good. The path managers and the guardian are removed as well. Only
+    /// callable by the admin.
+    RenounceAdmin {},
+    /// Updates the contract config. Only callable by the admin.
+    UpdateConfig {
+        fee_bps: Option<u16>,
+        fee_recipient: Option<String>,
+        default_hop_penalty: Option<HopPenalty>,
+        /// Removes the default hop penalty if true. Can not be combined with
+        /// `default_hop_penalty`.
+        clear_default_hop_penalty: Option<bool>,
+        /// Whether only the assets in the allowlist may be routed. While
+        /// enabled, an empty allowlist denies all assets.
+        allowlist_enabled: Option<bool>,
+    },
+    /// Sets or removes the guardian. Only callable by the admin.
+    SetGuardian {
+        guardian: Option<String>,
+    },
+    /// Pauses all swaps, including swaps of received Cw20 tokens. Queries and
+    /// admin messages keep working. Callable by the admin or the guardian.
+    Pause {},
+    /// Resumes swaps. Callable by the admin or the guardian.
+    Unpause {},
+    /// Blocks a pool, so that paths through it are rejected and skipped by
+    /// the best path queries. Callable by the admin or the guardian.
+    BlockPool {
+        pool: Pool,
+    },
+    /// Unblocks a pool. Callable by the admin or the guardian.
+    UnblockPool {
+        pool: Pool,
+    },
+    /// Adds and removes assets from the allowlist. While `allowlist_enabled`
+    /// is set in the config, only the assets in it may be routed. Only
+    /// callable by the admin.
+    UpdateAllowedAssets {
+        add: Vec<AssetInfoUnchecked>,
+        remove: Vec<AssetInfoUnchecked>,
+    },
+    /// Adds and removes assets from the denylist. The assets in it may never
+    /// be routed, even if they are in the allowlist. Only callable by the
+    /// admin.
+    UpdateDeniedAssets {
+        add: Vec<AssetInfoUnchecked>,
+        remove: Vec<AssetInfoUnchecked>,
+    },
     Callback(CallbackMsg),
 }
 
 #[cw_serde]
 pub enum CallbackMsg {
-    ExecuteSwapOperation {
-        operation: SwapOperation,
+    /// Swaps `offer_amount` through the first of `operations`. Each following
+    /// operation is executed from the reply to the previous swap, with the
+    /// exact amount it returned. The output of the last operation is sent to
+    /// `to`.
+    ExecuteSwapOperations {
+        operations: SwapOperationsList,
         to: Addr,
+        offer_amount: Uint128,
+    },
+    /// Takes the protocol fee from the amount of `asset_info` the contract
+    /// received since `prev_balance` and sends the rest to `recipient`.
+    CollectFee {
+        asset_info: AssetInfo,
+        prev_balance: Uint128,
+        recipient: Addr,
     },
+    /// Asserts that `recipient` received at least `minimum_receive` of
+    /// `asset_info` since `prev_balance`, and reports the result of the swap.
+    /// The received amount is returned as a [`SwapResponse`] in the data.
     AssertMinimumReceive {
         asset_info: AssetInfo,
         prev_balance: Uint128,
-        token_in: Asset,
-        minimum_receive: Uint128,
+        token_in: AssetList,
+        minimum_receive: Option<Uint128>,
+        recipient: Addr,
+    },
+    /// Sends the amount by which the contract's balance of each asset exceeds
+    /// its balance in `prev_balances` to `recipient`.
+    RefundLeftover {
+        prev_balances: AssetList,
         recipient: Addr,
     },
 }
@@ -65,7 +236,19 @@ pub enum Cw20HookMsg {
     ExecuteSwapOperations {
         operations: SwapOperationsListUnchecked,
         minimum_receive: Option<Uint128>,
+        /// See `ExecuteMsg::ExecuteSwapOperations`.
+        max_spread: Option<Decimal>,
+        /// See `ExecuteMsg::ExecuteSwapOperations`.
+        belief_price: Option<Decimal>,
         to: Option<String>,
+        /// See `ExecuteMsg::ExecuteSwapOperations`.
+        deadline: Option<Expiration>,
+    },
+    SwapBestPath {
+        ask_asset: AssetInfoUnchecked,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        exclude_paths: Option<Vec<u64>>,
     },
 }
 
@@ -73,48 +256,279 @@ pub enum Cw20HookMsg {
 pub struct BestPathForPairResponse {
     /// the operations that will be executed to perform the swap
     pub operations: crate::operations::SwapOperationsList,
-    /// the amount of tokens that are expected to be received after the swap
+    /// the amount of tokens returned by the swap before the protocol fee
     pub return_amount: Uint128,
+    /// the protocol fee taken from the return amount
+    pub fee_amount: Uint128,
+    /// the amount of tokens received by the recipient
+    pub net_amount: Uint128,
+    /// the return amount before the protocol fee minus the hop penalty, used
+    /// to rank the paths
+    pub penalized_return_amount: Uint128,
+    /// the paths that were skipped because their simulation failed
+    pub failed_paths: Vec<FailedPath>,
+}
+
+#[cw_serde]
+pub struct FailedPath {
+    /// the id of the stored path
+    pub path_id: u64,
+    /// the error returned by the simulation
+    pub error: String,
+}
+
+/// Set as the data of the response of all swaps.
+#[cw_serde]
+pub struct SwapResponse {
+    /// the amount of the ask asset received by the recipient
+    pub return_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct AdminResponse {
+    /// the current admin, or `None` if the admin role was renounced
+    pub admin: Option<Addr>,
+    /// the address proposed to become the new admin
+    pub pending_admin: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct PauseInfoResponse {
+    /// whether swaps are paused
+    pub paused: bool,
+    /// the address that may pause the router and block pools besides the admin
+    pub guardian: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct SimulateSwapResponse {
+    /// the amount of tokens received from the swap before the protocol fee
+    pub gross_amount: Uint128,
+    /// the protocol fee taken from the gross amount
+    pub fee_amount: Uint128,
+    /// the amount of tokens received by the recipient
+    pub net_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateReverseSwapResponse {
+    /// the amount of the offer asset needed to receive the ask amount
+    pub offer_amount: Uint128,
+    /// the amount of tokens the swap must return before the protocol fee
+    pub gross_amount: Uint128,
+    /// the protocol fee taken from the gross amount
+    pub fee_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct PathSimulation {
+    /// the id of the stored path
+    pub path_id: u64,
+    /// the operations of the path
+    pub operations: SwapOperationsList,
+    /// the amount of tokens returned by the swap before the protocol fee, or
+    /// `None` if the simulation failed
+    pub return_amount: Option<Uint128>,
+    /// the protocol fee taken from the return amount
+    pub fee_amount: Option<Uint128>,
+    /// the amount of tokens received by the recipient
+    pub net_amount: Option<Uint128>,
+    /// the error returned by the simulation, if it failed
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct BestSplitForPairResponse {
+    /// the amount of the offer asset to swap through each path, in the format
+    /// expected by `ExecuteMsg::ExecuteSplitSwap`
+    pub routes: Vec<(Uint128, SwapOperationsList)>,
+    /// the total amount of tokens returned by the routes before the protocol
+    /// fee
+    pub return_amount: Uint128,
+    /// the protocol fee taken from the return amount
+    pub fee_amount: Uint128,
+    /// the amount of tokens received by the recipient
+    pub net_amount: Uint128,
+}
+
+#[cw_serde]
+pub struct DiscoveredPath {
+    /// the operations that will be executed to perform the swap
+    pub operations: SwapOperationsList,
+    /// the amount of tokens returned by the swap before the protocol fee
+    pub return_amount: Uint128,
+    /// the protocol fee taken from the return amount
+    pub fee_amount: Uint128,
+    /// the amount of tokens received by the recipient
+    pub net_amount: Uint128,
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(Uint128)]
+    #[returns(SimulateSwapResponse)]
     SimulateSwapOperations {
         offer_amount: Uint128,
         operations: SwapOperationsListUnchecked,
     },
 
-    // #[returns(Uint128)]
-    // SimulateBasketLiquidate {
-    //     offer_assets: AssetListUnchecked,
-    //     receive_asset: AssetInfoUnchecked,
-    // },
-    /// Returns all the current path for a given (offer_asset, ask_asset) pair.
-    #[returns(Vec<crate::operations::SwapOperationsList>)]
+    /// Returns the amount of the offer asset needed to receive `ask_amount`
+    /// after the protocol fee, simulating the operations backwards.
+    #[returns(SimulateReverseSwapResponse)]
+    SimulateReverseSwapOperations {
+        ask_amount: Uint128,
+        operations: SwapOperationsListUnchecked,
+    },
+
+    /// Returns the total amount of `receive_asset` received when liquidating
+    /// all of `offer_assets` through their best stored paths.
+    #[returns(SimulateSwapResponse)]
+    SimulateBasketLiquidate {
+        offer_assets: AssetListUnchecked,
+        receive_asset: AssetInfoUnchecked,
+    },
+
+    /// Returns the combined amount received when executing each route with its
+    /// amount of the offer asset. Each route is simulated on its own, so
+    /// routes that share a pool are not accounted for.
+    #[returns(SimulateSwapResponse)]
+    SimulateSplitSwap {
+        routes: Vec<(Uint128, SwapOperationsListUnchecked)>,
+    },
+
+    /// Returns all the current paths for a given (offer_asset, ask_asset)
+    /// pair with their path ids.
+    #[returns(Vec<(u64, crate::operations::SwapOperationsList)>)]
     PathsForPair {
         offer_asset: AssetInfoUnchecked,
         ask_asset: AssetInfoUnchecked,
     },
+    /// Simulates `offer_amount` through every stored path for a given
+    /// (offer_asset, ask_asset) pair. A path whose simulation fails is
+    /// returned with its error instead of failing the query.
+    #[returns(Vec<PathSimulation>)]
+    SimulatePathsForPair {
+        offer_asset: AssetInfoUnchecked,
+        offer_amount: Uint128,
+        ask_asset: AssetInfoUnchecked,
+    },
     /// finds the best path for a given (offer_asset, ask_asset) pair.
-    /// if no path is found, returns None.
-    #[returns(Option<BestPathForPairResponse>)]
+    /// Paths whose simulation fails are skipped and reported in the response.
+    /// Returns an error if every path fails.
+    /// Paths are ranked by their return amount minus `hop_penalty` for each
+    /// operation. If `hop_penalty` is not set, the default from the config
+    /// is used.
+    #[returns(BestPathForPairResponse)]
     BestPathForPair {
         offer_asset: AssetInfoUnchecked,
         offer_amount: Uint128,
         ask_asset: AssetInfoUnchecked,
         exclude_paths: Option<Vec<u64>>,
+        hop_penalty: Option<HopPenalty>,
     },
 
-    /// Returns all the assets from which there are paths to a given ask asset.
+    /// Finds an allocation of `offer_amount` over the stored paths for a given
+    /// (offer_asset, ask_asset) pair. The offer amount is split into
+    /// `granularity` chunks and each chunk is given to the path with the best
+    /// marginal return, using at most `max_splits` different paths.
+    #[returns(BestSplitForPairResponse)]
+    BestSplitForPair {
+        offer_asset: AssetInfoUnchecked,
+        offer_amount: Uint128,
+        ask_asset: AssetInfoUnchecked,
+        max_splits: Option<u32>,
+        granularity: Option<u32>,
+    },
+
+    /// Searches the registered pools for paths between a given (offer_asset,
+    /// ask_asset) pair with at most `max_hops` operations. Returns the paths
+    /// sorted by simulated return amount, best first. Paths are searched by
+    /// increasing number of hops and the search stops once
+    /// `MAX_SIMULATED_ROUTES` routes are found. Blocked pools,
+    /// paths through disallowed assets and paths whose simulation fails are
+    /// left out.
+    #[returns(Vec<DiscoveredPath>)]
+    DiscoverPaths {
+        offer_asset: AssetInfoUnchecked,
+        ask_asset: AssetInfoUnchecked,
+        max_hops: u32,
+        offer_amount: Uint128,
+    },
+
+    /// Returns the pools registered for path discovery.
+    #[returns(Vec<(u64, crate::state::RegisteredPool)>)]
+    RegisteredPools {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the contract config.
+    #[returns(crate::state::Config)]
+    Config {},
+
+    /// Returns the path managers.
+    #[returns(Vec<Addr>)]
+    PathManagers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the current admin and the proposed new admin.
+    #[returns(AdminResponse)]
+    Admin {},
+
+    /// Returns whether the router is paused and the guardian.
+    #[returns(PauseInfoResponse)]
+    PauseInfo {},
+
+    /// Returns the blocked pools, ordered by their JSON serialization.
+    #[returns(Vec<Pool>)]
+    BlockedPools {
+        start_after: Option<Pool>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the assets in the allowlist.
+    #[returns(Vec<AssetInfo>)]
+    AllowedAssets {
+        start_after: Option<AssetInfoUnchecked>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the assets in the denylist.
     #[returns(Vec<AssetInfo>)]
-    SupportedOfferAssets { ask_asset: AssetInfoUnchecked },
+    DeniedAssets {
+        start_after: Option<AssetInfoUnchecked>,
+        limit: Option<u32>,
+    },
 
-    /// Returns all the assets to which there are paths from a given offer
-    /// asset.
+    /// Returns the assets from which there are usable paths to a given ask
+    /// asset, ordered by asset and without duplicates. Paths through blocked
+    /// pools or disallowed assets are not usable.
     #[returns(Vec<AssetInfo>)]
-    SupportedAskAssets { offer_asset: AssetInfoUnchecked },
+    SupportedOfferAssets {
+        ask_asset: AssetInfoUnchecked,
+        start_after: Option<AssetInfoUnchecked>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the assets to which there are usable paths from a given offer
+    /// asset, ordered by asset and without duplicates. Paths through blocked
+    /// pools or disallowed assets are not usable.
+    #[returns(Vec<AssetInfo>)]
+    SupportedAskAssets {
+        offer_asset: AssetInfoUnchecked,
+        start_after: Option<AssetInfoUnchecked>,
+        limit: Option<u32>,
+    },
+
+    /// Returns all stored paths as (offer_asset, ask_asset, path_id, path),
+    /// ordered by key.
+    #[returns(Vec<(AssetInfo, AssetInfo, u64, SwapOperationsList)>)]
+    AllPaths {
+        start_after: Option<(AssetInfoUnchecked, AssetInfoUnchecked, u64)>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -1,16 +1,26 @@
 use crate::msg::CallbackMsg;
+use crate::state::{is_asset_allowed, is_pool_blocked};
 use crate::ContractError;
 use apollo_cw_asset::{Asset, AssetInfo, AssetInfoBase};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, CosmosMsg, Deps, Env, Response, Uint128};
+use cosmwasm_std::{to_json_string, Addr, CosmosMsg, Decimal, Deps, Env, Event, Response, Uint128};
 use cw_dex::traits::Pool as PoolTrait;
 use cw_dex::Pool;
 
+/// The share of the offer amount that is simulated to estimate the rate of a
+/// pool before a swap, when checking the price impact of an operation.
+const PRICE_IMPACT_REFERENCE_RATIO: u128 = 1_000;
+
 #[cw_serde]
 pub struct SwapOperationBase<T> {
     pub pool: Pool,
     pub offer_asset_info: AssetInfoBase<T>,
     pub ask_asset_info: AssetInfoBase<T>,
+    /// The minimum amount of the ask asset this operation must return.
+    pub min_out: Option<Uint128>,
+    /// The maximum price impact of this operation, compared to the rate of
+    /// swapping a small fraction of the offer amount.
+    pub max_price_impact: Option<Decimal>,
 }
 
 impl<T> SwapOperationBase<T> {
@@ -23,6 +33,8 @@ impl<T> SwapOperationBase<T> {
             pool,
             offer_asset_info,
             ask_asset_info,
+            min_out: None,
+            max_price_impact: None,
         }
     }
 }
@@ -37,8 +49,21 @@ impl SwapOperationUnchecked {
             ask_asset_info: self.ask_asset_info.check(deps.api)?,
             offer_asset_info: self.offer_asset_info.check(deps.api)?,
             pool: self.pool.clone(),
+            min_out: self.min_out,
+            max_price_impact: self.max_price_impact,
         };
 
+        if matches!(op.max_price_impact, Some(x) if x > Decimal::one()) {
+            return Err(ContractError::InvalidSwapOperations {
+                operations: vec![op],
+                reason: "The max price impact must be at most 1".to_string(),
+            });
+        }
+
+        if is_pool_blocked(deps.storage, &op.pool)? {
+            return Err(ContractError::PoolBlocked { pool: op.pool });
+        }
+
         // validate pool assets
         let pool_assets = op.pool.pool_assets(deps)?;
 
@@ -64,27 +89,110 @@ impl SwapOperation {
         recipient: Addr,
     ) -> Result<Response, ContractError> {
         let offer_asset = Asset::new(self.offer_asset_info.clone(), offer_amount);
-        let minimum_receive = minimum_receive.unwrap_or(Uint128::one());
+
+        // Simulate swap to know how much will be returned, and fail before
+        // swapping if the operation's limits are not met
+        let receive_amount =
+            self.pool
+                .simulate_swap(deps, offer_asset.clone(), self.ask_asset_info.clone())?;
+        self.assert_limits(deps, offer_amount, receive_amount)?;
+
+        let minimum_receive = minimum_receive
+            .into_iter()
+            .chain(self.min_out)
+            .max()
+            .unwrap_or(Uint128::one());
 
         let mut response = self.pool.swap(
             deps,
             env,
-            offer_asset.clone(),
+            offer_asset,
             self.ask_asset_info.clone(),
             minimum_receive,
         )?;
 
+        let event = Event::new("apollo/cw-dex-router/callback_execute_swap_operation")
+            .add_attribute("pool", to_json_string(&self.pool)?)
+            .add_attribute("offer_asset", self.offer_asset_info.to_string())
+            .add_attribute("offer_amount", offer_amount)
+            .add_attribute("ask_asset", self.ask_asset_info.to_string())
+            .add_attribute("simulated_ask_amount", receive_amount)
+            .add_attribute("to", recipient.to_string());
+
         if recipient != env.contract.address {
-            // Simulate swap to know how much will be returned, then add message
-            // to send tokens to recipient
-            let receive_amount =
-                self.pool
-                    .simulate_swap(deps, offer_asset, self.ask_asset_info.clone())?;
+            // Add message to send the returned tokens to recipient
             let receive_asset = Asset::new(self.ask_asset_info.clone(), receive_amount);
             response = response.add_message(receive_asset.transfer_msg(recipient)?);
         }
 
-        Ok(response)
+        Ok(response.add_event(event))
+    }
+
+    /// Asserts that swapping `offer_amount` for `receive_amount` meets the
+    /// operation's `min_out` and `max_price_impact`.
+    pub fn assert_limits(
+        &self,
+        deps: Deps,
+        offer_amount: Uint128,
+        receive_amount: Uint128,
+    ) -> Result<(), ContractError> {
+        if let Some(min_out) = self.min_out {
+            if receive_amount < min_out {
+                return Err(ContractError::FailedOperationMinimumReceive {
+                    operation: self.clone(),
+                    wanted: min_out,
+                    got: receive_amount,
+                });
+            }
+        }
+
+        if let Some(max_price_impact) = self.max_price_impact {
+            let price_impact = self.price_impact(deps, offer_amount, receive_amount)?;
+            if price_impact > max_price_impact {
+                return Err(ContractError::MaxPriceImpactExceeded {
+                    operation: self.clone(),
+                    price_impact,
+                    max_price_impact,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the share by which `receive_amount` is lower than the amount
+    /// expected at the rate of swapping a small fraction of `offer_amount`.
+    /// Fails if that fraction returns nothing, e.g. because the offer amount
+    /// is tiny compared to the price of the ask asset, as the rate and with it
+    /// the price impact are unknown then.
+    fn price_impact(
+        &self,
+        deps: Deps,
+        offer_amount: Uint128,
+        receive_amount: Uint128,
+    ) -> Result<Decimal, ContractError> {
+        let reference_amount = offer_amount
+            .multiply_ratio(1u128, PRICE_IMPACT_REFERENCE_RATIO)
+            .max(Uint128::one());
+        let reference_receive_amount = self.pool.simulate_swap(
+            deps,
+            Asset::new(self.offer_asset_info.clone(), reference_amount),
+            self.ask_asset_info.clone(),
+        )?;
+        if reference_receive_amount.is_zero() {
+            return Err(ContractError::PriceImpactNotMeasurable {
+                operation: self.clone(),
+                reference_amount,
+            });
+        }
+
+        let expected_amount =
+            offer_amount.multiply_ratio(reference_receive_amount, reference_amount);
+        if receive_amount >= expected_amount {
+            return Ok(Decimal::zero());
+        }
+
+        Ok(Decimal::one() - Decimal::from_ratio(receive_amount, expected_amount))
     }
 }
 
@@ -94,6 +202,8 @@ impl From<&SwapOperation> for SwapOperationUnchecked {
             ask_asset_info: checked.ask_asset_info.clone().into(),
             offer_asset_info: checked.offer_asset_info.clone().into(),
             pool: checked.pool.clone(),
+            min_out: checked.min_out,
+            max_price_impact: checked.max_price_impact,
         }
     }
 }
@@ -138,6 +248,17 @@ impl SwapOperationsListUnchecked {
             prev_ask_asset = operation.ask_asset_info.clone();
         }
 
+        // Check that the path only routes allowed assets
+        for operation in operations.iter() {
+            for asset in [&operation.offer_asset_info, &operation.ask_asset_info] {
+                if !is_asset_allowed(deps.storage, asset)? {
+                    return Err(ContractError::AssetNotAllowed {
+                        asset: asset.to_string(),
+                    });
+                }
+            }
+        }
+
         // Check that the path never swaps through the same pool twice
         let mut unique_pools = vec![];
         for operation in operations.iter() {
@@ -170,6 +291,8 @@ impl SwapOperationsList {
                 let tmp = op.offer_asset_info.clone();
                 op.offer_asset_info = op.ask_asset_info.clone();
                 op.ask_asset_info = tmp;
+                // The minimum is denominated in the previous ask asset
+                op.min_out = None;
                 op
             })
             .collect::<Vec<SwapOperation>>();
@@ -177,29 +300,64 @@ impl SwapOperationsList {
         Self::new(operations)
     }
 
-    pub fn into_execute_msgs(
+    /// Returns the callback message that executes the operations in order.
+    /// The first operation swaps exactly `offer_amount`, every following
+    /// operation swaps exactly the amount the previous operation returned.
+    pub fn into_execute_msg(
         &self,
         env: &Env,
         recipient: Addr,
-    ) -> Result<Vec<CosmosMsg>, ContractError> {
-        let operations_len = self.0.len();
-        let mut msgs = vec![];
-        for (i, operation) in self.0.iter().enumerate() {
-            //Always send assets to self except for last operation
-            let to = if i == operations_len - 1 {
-                recipient.clone()
-            } else {
-                env.contract.address.clone()
-            };
-            msgs.push(
-                CallbackMsg::ExecuteSwapOperation {
-                    operation: operation.clone(),
-                    to,
-                }
-                .into_cosmos_msg(env)?,
-            )
+        offer_amount: Uint128,
+    ) -> Result<CosmosMsg, ContractError> {
+        CallbackMsg::ExecuteSwapOperations {
+            operations: self.clone(),
+            to: recipient,
+            offer_amount,
         }
-        Ok(msgs)
+        .into_cosmos_msg(env)
+    }
+
+    /// Returns the first operation and the operations following it, if any.
+    pub fn split_first(&self) -> (&SwapOperation, Option<SwapOperationsList>) {
+        let (first, rest) = self.0.split_first().unwrap();
+        let rest = if rest.is_empty() {
+            None
+        } else {
+            Some(Self::new(rest.to_vec()))
+        };
+        (first, rest)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the offer asset of every operation, i.e. the offer asset of
+    /// the path followed by its intermediate assets.
+    pub fn offer_asset_infos(&self) -> Vec<AssetInfo> {
+        self.0
+            .iter()
+            .map(|operation| operation.offer_asset_info.clone())
+            .collect()
+    }
+
+    /// Returns the pool of every operation.
+    pub fn pools(&self) -> Vec<Pool> {
+        self.0
+            .iter()
+            .map(|operation| operation.pool.clone())
+            .collect()
+    }
+
+    /// Returns whether any operation has a `min_out` or `max_price_impact`.
+    pub fn has_limits(&self) -> bool {
+        self.0
+            .iter()
+            .any(|operation| operation.min_out.is_some() || operation.max_price_impact.is_some())
     }
 
     pub fn from(&self) -> AssetInfo {
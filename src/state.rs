@@ -1,13 +1,150 @@
-use apollo_cw_asset::AssetInfoKey;
+use apollo_cw_asset::{AssetInfo, AssetInfoKey};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_string, Addr, Empty, StdResult, Storage, Uint128};
 use cw_controllers::Admin;
-use cw_storage_plus::Map;
+use cw_dex::Pool;
+use cw_storage_plus::{Item, Map};
 
 use crate::operations::SwapOperationsList;
 
-/// As an MVP we hardcode paths for each tuple of assets (offer, ask).
-/// In a future version we want to find the path that produces the highest
-/// number of ask assets, but this will take some time to implement.
+/// Paths are hardcoded by the admin for each tuple of assets (offer, ask).
+/// Paths over the pools in [`POOLS`] can also be found with the
+/// `DiscoverPaths` query, but only stored paths are used for execution.
 /// To support multiple paths between the same asset, we add an id field we increment per asset of the same
 /// path
 pub const PATHS: Map<(AssetInfoKey, AssetInfoKey, u64), SwapOperationsList> = Map::new("paths");
+/// The (ask, offer) asset pairs with at least one stored path, so that the
+/// offer assets with a path to an ask asset can be listed without scanning
+/// [`PATHS`].
+pub const OFFER_ASSETS_BY_ASK: Map<(AssetInfoKey, AssetInfoKey), Empty> =
+    Map::new("offer_assets_by_ask");
+/// The last path id used for each (offer, ask) asset pair. Ids are never
+/// reused, so that an id kept by a caller can't point to a different path
+/// after the path was removed.
+pub const LAST_PATH_IDS: Map<(AssetInfoKey, AssetInfoKey), u64> = Map::new("last_path_ids");
 pub const ADMIN: Admin = Admin::new("admin");
+/// Addresses that may store and remove paths in addition to the admin.
+pub const PATH_MANAGERS: Map<&Addr, Empty> = Map::new("path_managers");
+/// An address that may pause the router and block pools in addition to the
+/// admin, e.g. to react to an exploited pool without waiting for the admin.
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+/// Whether swaps are paused. Queries and admin messages are not affected.
+pub const PAUSED: Item<bool> = Item::new("paused");
+/// Pools that must not be swapped through, keyed by their JSON serialization.
+/// Paths over them are rejected when checked and skipped when looking for
+/// the best path.
+pub const BLOCKED_POOLS: Map<String, Empty> = Map::new("blocked_pools");
+/// The only assets that may be routed into, out of or through while
+/// [`Config::allowlist_enabled`] is set. If it is set and the allowlist is
+/// empty, no asset may be routed.
+pub const ALLOWED_ASSETS: Map<AssetInfoKey, Empty> = Map::new("allowed_assets");
+/// Assets that must never be routed into, out of or through.
+pub const DENIED_ASSETS: Map<AssetInfoKey, Empty> = Map::new("denied_assets");
+
+/// Returns whether `asset` may be routed according to [`ALLOWED_ASSETS`] and
+/// [`DENIED_ASSETS`].
+pub fn is_asset_allowed(storage: &dyn Storage, asset: &AssetInfo) -> StdResult<bool> {
+    if DENIED_ASSETS.has(storage, asset.into()) {
+        return Ok(false);
+    }
+    let allowlist_enabled = CONFIG
+        .may_load(storage)?
+        .map_or(false, |config| config.allowlist_enabled);
+    Ok(!allowlist_enabled || ALLOWED_ASSETS.has(storage, asset.into()))
+}
+
+/// Returns the key of `pool` in [`BLOCKED_POOLS`].
+pub fn blocked_pool_key(pool: &Pool) -> StdResult<String> {
+    to_json_string(pool)
+}
+
+/// Returns whether `pool` is in [`BLOCKED_POOLS`].
+pub fn is_pool_blocked(storage: &dyn Storage, pool: &Pool) -> StdResult<bool> {
+    Ok(BLOCKED_POOLS.has(storage, blocked_pool_key(pool)?))
+}
+
+/// The address proposed by the admin to become the new admin, which must
+/// accept the proposal.
+pub const PENDING_ADMIN: Item<Addr> = Item::new("pending_admin");
+
+/// The maximum protocol fee, in basis points.
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// The number of basis points in one, which is the denominator of the
+/// protocol fee and of hop penalties in basis points, and the maximum hop
+/// penalty.
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+#[cw_serde]
+pub struct Config {
+    /// The protocol fee taken from the output of routed swaps, in basis points.
+    pub fee_bps: u16,
+    /// The address that receives the protocol fee.
+    pub fee_recipient: Addr,
+    /// The hop penalty used by `BestPathForPair` and best path swaps when
+    /// none is given.
+    pub default_hop_penalty: Option<HopPenalty>,
+    /// Whether only the assets in [`ALLOWED_ASSETS`] may be routed.
+    pub allowlist_enabled: bool,
+}
+
+/// A penalty subtracted from the simulated return of a path for each of its
+/// operations, so that shorter paths are preferred unless a longer path
+/// returns enough more to make up for its gas cost.
+#[cw_serde]
+pub enum HopPenalty {
+    /// A fixed amount of the ask asset per operation.
+    Fixed(Uint128),
+    /// A share of the return amount per operation, in basis points.
+    Bps(u16),
+}
+
+impl HopPenalty {
+    /// Returns `return_amount` minus the penalty for `hops` operations.
+    pub fn apply(&self, return_amount: Uint128, hops: usize) -> Uint128 {
+        let hops = Uint128::from(hops as u128);
+        let penalty = match self {
+            HopPenalty::Fixed(amount) => amount.saturating_mul(hops),
+            HopPenalty::Bps(bps) => return_amount
+                .multiply_ratio(hops.saturating_mul(Uint128::from(*bps)), BPS_DENOMINATOR),
+        };
+        return_amount.saturating_sub(penalty)
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// A swap operation whose swap messages are in flight. The reply to the last
+/// swap message sizes the next operation, or sends the output to `to` after
+/// the last one.
+#[cw_serde]
+pub struct PendingSwap {
+    /// The index of the operation being executed in its path.
+    pub hop: u32,
+    /// The number of swap messages of the operation that have not replied yet.
+    pub pending_msgs: u32,
+    /// The operations that remain after the one being executed.
+    pub operations: Option<SwapOperationsList>,
+    /// The ask asset of the operation being executed.
+    pub ask_asset_info: AssetInfo,
+    /// The contract's balance of the ask asset before the swap.
+    pub prev_balance: Uint128,
+    /// The recipient of the output of the last operation.
+    pub to: Addr,
+}
+
+pub const PENDING_SWAP: Item<PendingSwap> = Item::new("pending_swap");
+
+/// A pool registered by the admin, together with the assets it contains.
+#[cw_serde]
+pub struct RegisteredPool {
+    pub pool: Pool,
+    pub pool_assets: Vec<AssetInfo>,
+}
+
+/// Pools that are used to build the token graph for path discovery, indexed
+/// by an id that is incremented for every new pool.
+pub const POOLS: Map<u64, RegisteredPool> = Map::new("pools");
+/// The id of the last registered pool. Ids are never reused after a pool is
+/// deregistered.
+pub const LAST_POOL_ID: Item<u64> = Item::new("last_pool_id");
@@ -0,0 +1,85 @@
+use apollo_cw_asset::{AssetInfoBase, AssetListUnchecked, AssetUnchecked};
+use cosmwasm_std::{Coin, Uint128};
+use cw_dex::osmosis::OsmosisPool;
+use osmosis_test_tube::{Account, Module, Wasm};
+
+use crate::msg::{ExecuteMsg, QueryMsg, SimulateSwapResponse};
+use crate::operations::{SwapOperationBase, SwapOperationsListUnchecked};
+use crate::tests::helpers::sort_tokens;
+
+use super::initialize::{default_init, query_balance};
+
+#[test]
+fn basket_liquidate_works() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    for pool in pools.clone() {
+        let _ = wasm
+            .execute(
+                &contract_address.to_string(),
+                &ExecuteMsg::SetPath {
+                    offer_asset: AssetInfoBase::Native(pool.denom0.clone()),
+                    ask_asset: AssetInfoBase::Native(pool.denom1.clone()),
+                    path: SwapOperationsListUnchecked::new(vec![SwapOperationBase {
+                        pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+                        offer_asset_info: AssetInfoBase::Native(pool.denom0.clone()),
+                        ask_asset_info: AssetInfoBase::Native(pool.denom1.clone()),
+                        min_out: None,
+                        max_price_impact: None,
+                    }]),
+                    bidirectional: true,
+                },
+                &[],
+                &admin,
+            )
+            .unwrap();
+    }
+
+    // pools are (denom0, denom1), (denom0, denom1), (denom0, denom2), (denom1, denom2)
+    let denom0 = pools[0].denom0.clone();
+    let denom1 = pools[0].denom1.clone();
+    let denom2 = pools[2].denom1.clone();
+
+    let offer_amount = Uint128::from(1000u128);
+    let offer_assets = AssetListUnchecked::from(vec![
+        AssetUnchecked::new(AssetInfoBase::Native(denom0.clone()), offer_amount),
+        AssetUnchecked::new(AssetInfoBase::Native(denom2.clone()), offer_amount),
+    ]);
+
+    let simulation: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateBasketLiquidate {
+                offer_assets: offer_assets.clone(),
+                receive_asset: AssetInfoBase::Native(denom1.clone()),
+            },
+        )
+        .unwrap();
+    let expected_out = simulation.net_amount;
+    assert!(!expected_out.is_zero());
+
+    let balance_before = query_balance(&app, &admin.address(), &denom1);
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::BasketLiquidate {
+                offer_assets,
+                receive_asset: AssetInfoBase::Native(denom1.clone()),
+                minimum_receive: Some(expected_out),
+                to: None,
+            },
+            &sort_tokens(vec![
+                Coin::new(offer_amount.u128(), denom0),
+                Coin::new(offer_amount.u128(), denom2),
+            ]),
+            &admin,
+        )
+        .unwrap();
+
+    assert_eq!(
+        query_balance(&app, &admin.address(), &denom1) - balance_before,
+        expected_out
+    );
+}
@@ -0,0 +1,319 @@
+use apollo_cw_asset::{AssetInfoBase, AssetUnchecked};
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw_dex::osmosis::OsmosisPool;
+use osmosis_test_tube::{Account, Module, OsmosisTestApp, SigningAccount, Wasm};
+
+use crate::msg::{
+    BestPathForPairResponse, ExecuteMsg, PathSimulation, QueryMsg, SimulateSwapResponse,
+};
+use crate::operations::{SwapOperationBase, SwapOperationsListUnchecked};
+use crate::state::HopPenalty;
+
+use super::initialize::{default_init, query_balance, two_hop_path, PoolWithDenoms};
+
+/// Stores a one hop and a two hop path from denom0 to denom1 of the first
+/// pool, and returns them in that order.
+fn set_paths(
+    app: &OsmosisTestApp,
+    contract_address: &Addr,
+    pools: &[PoolWithDenoms],
+    admin: &SigningAccount,
+) -> Vec<SwapOperationsListUnchecked> {
+    let wasm = Wasm::new(app);
+
+    let offer_denom = pools[0].denom0.clone();
+    let ask_denom = pools[0].denom1.clone();
+    let paths = vec![
+        SwapOperationsListUnchecked::new(vec![SwapOperationBase::new(
+            cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pools[1].pool)),
+            AssetInfoBase::Native(offer_denom.clone()),
+            AssetInfoBase::Native(ask_denom.clone()),
+        )]),
+        two_hop_path(pools),
+    ];
+
+    for path in &paths {
+        let _ = wasm
+            .execute(
+                &contract_address.to_string(),
+                &ExecuteMsg::SetPath {
+                    offer_asset: AssetInfoBase::Native(offer_denom.clone()),
+                    ask_asset: AssetInfoBase::Native(ask_denom.clone()),
+                    path: path.clone(),
+                    bidirectional: false,
+                },
+                &[],
+                admin,
+            )
+            .unwrap();
+    }
+
+    paths
+}
+
+#[test]
+fn hop_penalty_ranks_paths_by_penalized_return() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    let paths = set_paths(&app, &contract_address, &pools, &admin);
+    let offer_amount = Uint128::from(1_000_000u128);
+
+    let returns: Vec<Uint128> = paths
+        .iter()
+        .map(|path| {
+            wasm.query::<_, SimulateSwapResponse>(
+                &contract_address.to_string(),
+                &QueryMsg::SimulateSwapOperations {
+                    offer_amount,
+                    operations: path.clone(),
+                },
+            )
+            .unwrap()
+            .net_amount
+        })
+        .collect();
+
+    // A penalty of half the return of the two hop path makes the one hop path
+    // the better one unless it returns less than half as much
+    let hop_penalty = HopPenalty::Fixed(returns[1] / Uint128::new(2));
+    let penalized: Vec<Uint128> = returns
+        .iter()
+        .zip([1, 2])
+        .map(|(amount, hops)| hop_penalty.apply(*amount, hops))
+        .collect();
+    let expected = if penalized[1] > penalized[0] { 1 } else { 0 };
+
+    let best_path_query = |hop_penalty: Option<HopPenalty>| {
+        wasm.query::<_, BestPathForPairResponse>(
+            &contract_address.to_string(),
+            &QueryMsg::BestPathForPair {
+                offer_asset: AssetInfoBase::Native(pools[0].denom0.clone()),
+                offer_amount,
+                ask_asset: AssetInfoBase::Native(pools[0].denom1.clone()),
+                exclude_paths: None,
+                hop_penalty,
+            },
+        )
+        .unwrap()
+    };
+
+    let best_path = best_path_query(Some(hop_penalty.clone()));
+    assert_eq!(
+        SwapOperationsListUnchecked::from(best_path.operations),
+        paths[expected]
+    );
+    assert_eq!(best_path.return_amount, returns[expected]);
+    assert_eq!(best_path.penalized_return_amount, penalized[expected]);
+
+    // Without a penalty the raw and penalized returns are the same
+    let best_path = best_path_query(None);
+    assert_eq!(best_path.return_amount, best_path.penalized_return_amount);
+
+    // The default penalty from the config is used if none is given
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::UpdateConfig {
+                fee_bps: None,
+                fee_recipient: None,
+                default_hop_penalty: Some(hop_penalty),
+                clear_default_hop_penalty: None,
+                allowlist_enabled: None,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+    let best_path = best_path_query(None);
+    assert_eq!(best_path.penalized_return_amount, penalized[expected]);
+
+    // The default penalty can be cleared again
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::UpdateConfig {
+                fee_bps: None,
+                fee_recipient: None,
+                default_hop_penalty: None,
+                clear_default_hop_penalty: Some(true),
+                allowlist_enabled: None,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+    let best_path = best_path_query(None);
+    assert_eq!(best_path.return_amount, best_path.penalized_return_amount);
+}
+
+#[test]
+fn hop_penalty_above_max_bps_is_rejected() {
+    let (app, contract_address, _, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    let err = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::UpdateConfig {
+                fee_bps: None,
+                fee_recipient: None,
+                default_hop_penalty: Some(HopPenalty::Bps(10_001)),
+                clear_default_hop_penalty: None,
+                allowlist_enabled: None,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Hop penalty of 10001 bps"));
+}
+
+#[test]
+fn simulate_paths_for_pair_returns_every_path() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    let paths = set_paths(&app, &contract_address, &pools, &admin);
+    let offer_amount = Uint128::from(1_000_000u128);
+
+    let simulations: Vec<PathSimulation> = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulatePathsForPair {
+                offer_asset: AssetInfoBase::Native(pools[0].denom0.clone()),
+                offer_amount,
+                ask_asset: AssetInfoBase::Native(pools[0].denom1.clone()),
+            },
+        )
+        .unwrap();
+
+    assert_eq!(simulations.len(), paths.len());
+    for (simulation, path) in simulations.into_iter().zip(paths) {
+        let expected: SimulateSwapResponse = wasm
+            .query(
+                &contract_address.to_string(),
+                &QueryMsg::SimulateSwapOperations {
+                    offer_amount,
+                    operations: path.clone(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            SwapOperationsListUnchecked::from(simulation.operations),
+            path
+        );
+        assert_eq!(simulation.return_amount, Some(expected.gross_amount));
+        assert_eq!(simulation.fee_amount, Some(expected.fee_amount));
+        assert_eq!(simulation.net_amount, Some(expected.net_amount));
+        assert_eq!(simulation.error, None);
+    }
+}
+
+#[test]
+fn best_path_skips_blocked_pools() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    let paths = set_paths(&app, &contract_address, &pools, &admin);
+    let blocked_pool = cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pools[1].pool));
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::BlockPool {
+                pool: blocked_pool.clone(),
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+
+    let best_path: BestPathForPairResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::BestPathForPair {
+                offer_asset: AssetInfoBase::Native(pools[0].denom0.clone()),
+                offer_amount: Uint128::from(1_000_000u128),
+                ask_asset: AssetInfoBase::Native(pools[0].denom1.clone()),
+                exclude_paths: None,
+                hop_penalty: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        SwapOperationsListUnchecked::from(best_path.operations),
+        paths[1]
+    );
+    assert_eq!(best_path.failed_paths.len(), 1);
+    assert_eq!(best_path.failed_paths[0].path_id, 1);
+
+    // Paths through the blocked pool can no longer be stored
+    let err = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::SetPath {
+                offer_asset: AssetInfoBase::Native(pools[0].denom0.clone()),
+                ask_asset: AssetInfoBase::Native(pools[0].denom1.clone()),
+                path: paths[0].clone(),
+                bidirectional: false,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("is blocked"));
+}
+
+#[test]
+fn swap_best_path_executes_best_stored_path() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    let _ = set_paths(&app, &contract_address, &pools, &admin);
+    let offer_amount = Uint128::from(1_000_000u128);
+    let offer_denom = pools[0].denom0.clone();
+    let ask_denom = pools[0].denom1.clone();
+
+    let best_path: BestPathForPairResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::BestPathForPair {
+                offer_asset: AssetInfoBase::Native(offer_denom.clone()),
+                offer_amount,
+                ask_asset: AssetInfoBase::Native(ask_denom.clone()),
+                exclude_paths: None,
+                hop_penalty: None,
+            },
+        )
+        .unwrap();
+    let expected: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: best_path.operations.into(),
+            },
+        )
+        .unwrap();
+
+    let ask_balance_before = query_balance(&app, &admin.address(), &ask_denom);
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::SwapBestPath {
+                offer_asset: AssetUnchecked::new(
+                    AssetInfoBase::Native(offer_denom.clone()),
+                    offer_amount,
+                ),
+                ask_asset: AssetInfoBase::Native(ask_denom.clone()),
+                minimum_receive: Some(expected.net_amount),
+                to: None,
+                exclude_paths: None,
+            },
+            &[Coin::new(offer_amount.u128(), offer_denom)],
+            &admin,
+        )
+        .unwrap();
+
+    assert_eq!(
+        query_balance(&app, &admin.address(), &ask_denom) - ask_balance_before,
+        expected.net_amount
+    );
+}
@@ -0,0 +1,360 @@
+use apollo_cw_asset::{Asset, AssetInfo, AssetInfoBase};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::{to_json_binary, Addr, Deps, QuerierWrapper, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse};
+use cw_dex::astroport::AstroportPool;
+use cw_dex::Pool;
+use osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
+use osmosis_std::types::cosmos::base::v1beta1::Coin as BaseCoin;
+use osmosis_test_tube::{Account, Bank, Module, OsmosisTestApp, SigningAccount, Wasm};
+
+use crate::helpers::CwDexRouter;
+use crate::msg::{Cw20HookMsg, ExecuteMsg};
+use crate::operations::{
+    SwapOperation, SwapOperationBase, SwapOperationsList, SwapOperationsListUnchecked,
+};
+
+use super::helpers::{execute_wasm_msg, TestQuerier};
+use super::initialize::{default_init, query_balance};
+
+/// Path of the cw20-base contract downloaded by the `cw20-wasm` task
+const CW20_BASE_WASM: &str = "./test-tube-build/cw20_base.wasm";
+/// Path of the mock Astroport pair built by the `mock-pair-wasm` task
+const MOCK_PAIR_WASM: &str =
+    "./test-tube-build/wasm32-unknown-unknown/release/mock_astroport_pair.wasm";
+
+/// The Cw20 balance of the admin and the native balance of the mock pair
+const INITIAL_BALANCE: u128 = 1_000_000;
+
+#[cw_serde]
+struct Cw20InstantiateMsg {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    initial_balances: Vec<Cw20Coin>,
+    mint: Option<MinterResponse>,
+}
+
+#[cw_serde]
+struct MockPairInstantiateMsg {
+    token: String,
+    denom: String,
+}
+
+/// Instantiates a Cw20 token held by `admin` and a mock Astroport pair that
+/// swaps it 1:1 for `denom`, funded with `denom` by `admin`. Returns the
+/// addresses of the token and the pair, and the pool of the pair.
+pub fn init_cw20_pool(
+    app: &OsmosisTestApp,
+    admin: &SigningAccount,
+    denom: &str,
+) -> (Addr, Addr, Pool) {
+    let wasm = Wasm::new(app);
+
+    let code_id = wasm
+        .store_code(&std::fs::read(CW20_BASE_WASM).unwrap(), None, admin)
+        .unwrap()
+        .data
+        .code_id;
+    let token = wasm
+        .instantiate(
+            code_id,
+            &Cw20InstantiateMsg {
+                name: "Test token".to_string(),
+                symbol: "TEST".to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: admin.address(),
+                    amount: Uint128::from(INITIAL_BALANCE),
+                }],
+                mint: None,
+            },
+            None,
+            Some("cw20"),
+            &[],
+            admin,
+        )
+        .unwrap()
+        .data
+        .address;
+
+    let code_id = wasm
+        .store_code(&std::fs::read(MOCK_PAIR_WASM).unwrap(), None, admin)
+        .unwrap()
+        .data
+        .code_id;
+    let pair = wasm
+        .instantiate(
+            code_id,
+            &MockPairInstantiateMsg {
+                token: token.clone(),
+                denom: denom.to_string(),
+            },
+            None,
+            Some("mock-astroport-pair"),
+            &[],
+            admin,
+        )
+        .unwrap()
+        .data
+        .address;
+    Bank::new(app)
+        .send(
+            MsgSend {
+                from_address: admin.address(),
+                to_address: pair.clone(),
+                amount: vec![BaseCoin {
+                    denom: denom.to_string(),
+                    amount: INITIAL_BALANCE.to_string(),
+                }],
+            },
+            admin,
+        )
+        .unwrap();
+
+    // The pool is read from the pair, the storage and api are not used
+    let (storage, api) = (MockStorage::new(), MockApi::default());
+    let test_querier = TestQuerier(app);
+    let deps = Deps {
+        storage: &storage,
+        api: &api,
+        querier: QuerierWrapper::new(&test_querier),
+    };
+    let pair = Addr::unchecked(pair);
+    let pool = Pool::Astroport(AstroportPool::new(deps, pair.clone()).unwrap());
+
+    (Addr::unchecked(token), pair, pool)
+}
+
+/// Returns the balance of `token` held by `address`.
+pub fn query_cw20_balance(app: &OsmosisTestApp, token: &Addr, address: &str) -> Uint128 {
+    Wasm::new(app)
+        .query::<_, BalanceResponse>(
+            token.as_str(),
+            &Cw20QueryMsg::Balance {
+                address: address.to_string(),
+            },
+        )
+        .unwrap()
+        .balance
+}
+
+#[test]
+fn cw20_offer_amount_is_pulled_with_transfer_from() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    let ask_denom = pools[0].denom1.clone();
+    let (token, pair, pool) = init_cw20_pool(&app, &admin, &ask_denom);
+
+    let offer_amount = Uint128::from(10000u128);
+    let swap_msg = ExecuteMsg::ExecuteSwapOperations {
+        operations: SwapOperationsListUnchecked::new(vec![SwapOperationBase::new(
+            pool,
+            AssetInfoBase::Cw20(token.to_string()),
+            AssetInfoBase::Native(ask_denom.clone()),
+        )]),
+        offer_amount: Some(offer_amount),
+        minimum_receive: Some(offer_amount),
+        max_spread: None,
+        belief_price: None,
+        to: None,
+        deadline: None,
+    };
+
+    // Without an allowance the TransferFrom of the offer amount is rejected
+    // by the token
+    let err = wasm
+        .execute(&contract_address.to_string(), &swap_msg, &[], &admin)
+        .unwrap_err();
+    assert!(err.to_string().contains("No allowance for this account"));
+
+    let _ = wasm
+        .execute(
+            token.as_str(),
+            &Cw20ExecuteMsg::IncreaseAllowance {
+                spender: contract_address.to_string(),
+                amount: offer_amount,
+                expires: None,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+    let ask_balance_before = query_balance(&app, &admin.address(), &ask_denom);
+
+    let _ = wasm
+        .execute(&contract_address.to_string(), &swap_msg, &[], &admin)
+        .unwrap();
+
+    // Exactly the offer amount was pulled from the sender and swapped in the
+    // pair, and the router does not keep any of it
+    assert_eq!(
+        query_cw20_balance(&app, &token, &admin.address()),
+        Uint128::from(INITIAL_BALANCE) - offer_amount
+    );
+    assert_eq!(
+        query_cw20_balance(&app, &token, pair.as_str()),
+        offer_amount
+    );
+    assert_eq!(
+        query_cw20_balance(&app, &token, contract_address.as_str()),
+        Uint128::zero()
+    );
+    assert_eq!(
+        query_balance(&app, &admin.address(), &ask_denom) - ask_balance_before,
+        offer_amount
+    );
+}
+
+#[test]
+fn cw20_hook_swaps_best_path() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    let ask_denom = pools[0].denom1.clone();
+    let (token, pair, pool) = init_cw20_pool(&app, &admin, &ask_denom);
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::SetPath {
+                offer_asset: AssetInfoBase::Cw20(token.to_string()),
+                ask_asset: AssetInfoBase::Native(ask_denom.clone()),
+                path: SwapOperationsListUnchecked::new(vec![SwapOperationBase::new(
+                    pool,
+                    AssetInfoBase::Cw20(token.to_string()),
+                    AssetInfoBase::Native(ask_denom.clone()),
+                )]),
+                bidirectional: false,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+
+    // The offer amount is sent with the hook, so no allowance is needed
+    let offer_amount = Uint128::from(10000u128);
+    let ask_balance_before = query_balance(&app, &admin.address(), &ask_denom);
+    let _ = wasm
+        .execute(
+            token.as_str(),
+            &Cw20ExecuteMsg::Send {
+                contract: contract_address.to_string(),
+                amount: offer_amount,
+                msg: to_json_binary(&Cw20HookMsg::SwapBestPath {
+                    ask_asset: AssetInfoBase::Native(ask_denom.clone()),
+                    minimum_receive: Some(offer_amount),
+                    to: None,
+                    exclude_paths: None,
+                })
+                .unwrap(),
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+
+    assert_eq!(
+        query_cw20_balance(&app, &token, &admin.address()),
+        Uint128::from(INITIAL_BALANCE) - offer_amount
+    );
+    assert_eq!(
+        query_cw20_balance(&app, &token, pair.as_str()),
+        offer_amount
+    );
+    assert_eq!(
+        query_cw20_balance(&app, &token, contract_address.as_str()),
+        Uint128::zero()
+    );
+    assert_eq!(
+        query_balance(&app, &admin.address(), &ask_denom) - ask_balance_before,
+        offer_amount
+    );
+}
+
+#[test]
+fn cw20_swap_helpers_execute() {
+    let (app, contract_address, pools, admin) = default_init();
+    let router = CwDexRouter::new(&contract_address);
+    let ask_denom = pools[0].denom1.clone();
+    let ask_asset = AssetInfo::Native(ask_denom.clone());
+    let (token, pair, pool) = init_cw20_pool(&app, &admin, &ask_denom);
+    let path = SwapOperationsList::new(vec![SwapOperation::new(
+        pool,
+        AssetInfo::Cw20(token.clone()),
+        ask_asset.clone(),
+    )]);
+    execute_wasm_msg(
+        &app,
+        router
+            .set_path_msg(
+                AssetInfo::Cw20(token.clone()),
+                ask_asset.clone(),
+                &path,
+                false,
+            )
+            .unwrap(),
+        &admin,
+    )
+    .unwrap();
+
+    // Both helpers send the offer amount to the router with a Cw20 `Send`
+    let offer_asset = Asset::new(AssetInfo::Cw20(token.clone()), 10000u128);
+    let ask_balance_before = query_balance(&app, &admin.address(), &ask_denom);
+    let msgs = vec![
+        router
+            .execute_swap_operations_cw20_msg(
+                &offer_asset,
+                &path,
+                Some(offer_asset.amount),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+        router
+            .swap_best_path_msg(
+                &offer_asset,
+                &ask_asset,
+                Some(offer_asset.amount),
+                None,
+                None,
+            )
+            .unwrap(),
+    ];
+    for msg in msgs {
+        execute_wasm_msg(&app, msg, &admin).unwrap();
+    }
+
+    let swapped_amount = offer_asset.amount * Uint128::new(2);
+    assert_eq!(
+        query_cw20_balance(&app, &token, &admin.address()),
+        Uint128::from(INITIAL_BALANCE) - swapped_amount
+    );
+    assert_eq!(
+        query_cw20_balance(&app, &token, pair.as_str()),
+        swapped_amount
+    );
+    assert_eq!(
+        query_cw20_balance(&app, &token, contract_address.as_str()),
+        Uint128::zero()
+    );
+    assert_eq!(
+        query_balance(&app, &admin.address(), &ask_denom) - ask_balance_before,
+        swapped_amount
+    );
+
+    // A native offer asset can not be sent to the router with a Cw20 Send
+    assert!(router
+        .execute_swap_operations_cw20_msg(
+            &Asset::new(ask_asset, 100u128),
+            &path,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .is_err());
+}
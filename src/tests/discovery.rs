@@ -0,0 +1,138 @@
+use apollo_cw_asset::AssetInfoBase;
+use cosmwasm_std::{Addr, Uint128};
+use cw_dex::osmosis::OsmosisPool;
+use osmosis_test_tube::{Module, OsmosisTestApp, SigningAccount, Wasm};
+
+use crate::msg::{DiscoveredPath, ExecuteMsg, QueryMsg, SimulateSwapResponse};
+use crate::operations::SwapOperationsListUnchecked;
+use crate::state::RegisteredPool;
+
+use super::initialize::{default_init, PoolWithDenoms};
+
+/// Registers the balancer pools, which connect the denoms of the second pool
+/// directly and through the third denom.
+fn register_balancer_pools(
+    wasm: &Wasm<OsmosisTestApp>,
+    contract_address: &Addr,
+    pools: &[PoolWithDenoms],
+    admin: &SigningAccount,
+) {
+    for pool in &pools[1..] {
+        let _ = wasm
+            .execute(
+                &contract_address.to_string(),
+                &ExecuteMsg::RegisterPool {
+                    pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+                },
+                &[],
+                admin,
+            )
+            .unwrap();
+    }
+}
+
+#[test]
+fn discover_paths_over_registered_pools() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    register_balancer_pools(&wasm, &contract_address, &pools, &admin);
+
+    let offer_amount = Uint128::from(1_000_000u128);
+    let discovered: Vec<DiscoveredPath> = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::DiscoverPaths {
+                offer_asset: AssetInfoBase::Native(pools[1].denom0.clone()),
+                ask_asset: AssetInfoBase::Native(pools[1].denom1.clone()),
+                max_hops: 2,
+                offer_amount,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(discovered.len(), 2);
+    let mut hops: Vec<usize> = discovered.iter().map(|x| x.operations.len()).collect();
+    hops.sort();
+    assert_eq!(hops, vec![1, 2]);
+    assert!(discovered[0].return_amount >= discovered[1].return_amount);
+    for path in discovered {
+        let simulation: SimulateSwapResponse = wasm
+            .query(
+                &contract_address.to_string(),
+                &QueryMsg::SimulateSwapOperations {
+                    offer_amount,
+                    operations: SwapOperationsListUnchecked::from(path.operations),
+                },
+            )
+            .unwrap();
+        assert_eq!(simulation.gross_amount, path.return_amount);
+    }
+
+    // Pool ids are not reused after a pool is deregistered
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::DeregisterPool { pool_id: 3 },
+            &[],
+            &admin,
+        )
+        .unwrap();
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::RegisterPool {
+                pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pools[3].pool)),
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+    let registered: Vec<(u64, RegisteredPool)> = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::RegisteredPools {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        registered.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+        vec![1, 2, 4]
+    );
+}
+
+#[test]
+fn discover_paths_skips_blocked_pools() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    register_balancer_pools(&wasm, &contract_address, &pools, &admin);
+
+    let blocked_pool = cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pools[1].pool));
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::BlockPool {
+                pool: blocked_pool.clone(),
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+
+    // Only the route around the blocked pool is left
+    let discovered: Vec<DiscoveredPath> = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::DiscoverPaths {
+                offer_asset: AssetInfoBase::Native(pools[1].denom0.clone()),
+                ask_asset: AssetInfoBase::Native(pools[1].denom1.clone()),
+                max_hops: 2,
+                offer_amount: Uint128::from(1_000_000u128),
+            },
+        )
+        .unwrap();
+    assert_eq!(discovered.len(), 1);
+    assert_eq!(discovered[0].operations.len(), 2);
+    assert!(!discovered[0].operations.pools().contains(&blocked_pool));
+}
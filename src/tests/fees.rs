@@ -0,0 +1,116 @@
+use apollo_cw_asset::AssetInfoBase;
+use cosmwasm_std::{Coin, Uint128};
+use cw_dex::osmosis::OsmosisPool;
+use osmosis_test_tube::{Account, Module, Wasm};
+
+use crate::msg::{BestPathForPairResponse, ExecuteMsg, QueryMsg, SimulateSwapResponse};
+use crate::operations::{SwapOperationBase, SwapOperationsListUnchecked};
+
+use super::initialize::{default_init, query_balance, FEE_DENOM};
+
+#[test]
+fn protocol_fee_is_taken_from_swap_output() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    let fee_recipient = app
+        .init_account(&[Coin::new(1_000_000_000u128, FEE_DENOM)])
+        .unwrap();
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::UpdateConfig {
+                fee_bps: Some(100),
+                fee_recipient: Some(fee_recipient.address()),
+                default_hop_penalty: None,
+                clear_default_hop_penalty: None,
+                allowlist_enabled: None,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+
+    let pool = pools.first().unwrap().clone();
+    let operations = SwapOperationsListUnchecked::new(vec![SwapOperationBase {
+        pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+        offer_asset_info: AssetInfoBase::Native(pool.denom0.clone()),
+        ask_asset_info: AssetInfoBase::Native(pool.denom1.clone()),
+        min_out: None,
+        max_price_impact: None,
+    }]);
+    let offer_amount = Uint128::from(10000u128);
+
+    let simulation: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+    assert!(!simulation.fee_amount.is_zero());
+    assert_eq!(
+        simulation.gross_amount,
+        simulation.fee_amount + simulation.net_amount
+    );
+
+    // The best path query reports the same fee and net amount
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::SetPath {
+                offer_asset: AssetInfoBase::Native(pool.denom0.clone()),
+                ask_asset: AssetInfoBase::Native(pool.denom1.clone()),
+                path: operations.clone(),
+                bidirectional: false,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+    let best_path: BestPathForPairResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::BestPathForPair {
+                offer_asset: AssetInfoBase::Native(pool.denom0.clone()),
+                offer_amount,
+                ask_asset: AssetInfoBase::Native(pool.denom1.clone()),
+                exclude_paths: None,
+                hop_penalty: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(best_path.return_amount, simulation.gross_amount);
+    assert_eq!(best_path.fee_amount, simulation.fee_amount);
+    assert_eq!(best_path.net_amount, simulation.net_amount);
+
+    let balance_before = query_balance(&app, &admin.address(), &pool.denom1);
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations,
+                offer_amount: None,
+                minimum_receive: Some(simulation.net_amount),
+                max_spread: None,
+                belief_price: None,
+                to: None,
+                deadline: None,
+            },
+            &[Coin::new(offer_amount.u128(), pool.denom0.clone())],
+            &admin,
+        )
+        .unwrap();
+
+    assert_eq!(
+        query_balance(&app, &admin.address(), &pool.denom1) - balance_before,
+        simulation.net_amount
+    );
+    assert_eq!(
+        query_balance(&app, &fee_recipient.address(), &pool.denom1),
+        simulation.fee_amount
+    );
+}
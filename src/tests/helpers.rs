@@ -1,7 +1,79 @@
-use cosmwasm_std::Coin;
+use cosmwasm_std::{
+    from_json, Coin, ContractResult, CosmosMsg, Empty, Querier, QuerierResult, QueryRequest,
+    SystemError, SystemResult, WasmMsg, WasmQuery,
+};
+use osmosis_std::types::cosmos::base::v1beta1::Coin as BaseCoin;
+use osmosis_std::types::cosmwasm::wasm::v1::{
+    MsgExecuteContract, MsgExecuteContractResponse, QuerySmartContractStateRequest,
+    QuerySmartContractStateResponse,
+};
+use osmosis_test_tube::{Account, OsmosisTestApp, Runner, RunnerResult, SigningAccount};
 
 pub fn sort_tokens(tokens: Vec<Coin>) -> Vec<Coin> {
     let mut sorted_tokens = tokens;
     sorted_tokens.sort_by(|a, b| a.denom.cmp(&b.denom));
     sorted_tokens
 }
+
+/// Forwards smart queries to the test app, so that the query helpers of
+/// `CwDexRouter` can be used against the contract.
+pub struct TestQuerier<'a>(pub &'a OsmosisTestApp);
+
+impl Querier for TestQuerier<'_> {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        match from_json::<QueryRequest<Empty>>(bin_request) {
+            Ok(QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })) => {
+                let res = self
+                    .0
+                    .query::<_, QuerySmartContractStateResponse>(
+                        "/cosmwasm.wasm.v1.Query/SmartContractState",
+                        &QuerySmartContractStateRequest {
+                            address: contract_addr,
+                            query_data: msg.to_vec(),
+                        },
+                    )
+                    .map(|res| res.data.into())
+                    .map_err(|err| err.to_string());
+                SystemResult::Ok(ContractResult::from(res))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "only smart queries are supported".to_string(),
+            }),
+        }
+    }
+}
+
+/// Executes a message returned by a `CwDexRouter` helper. Only wasm execute
+/// messages are supported, e.g. messages to the router or a Cw20 `Send` of the
+/// offer asset to the router.
+pub fn execute_wasm_msg(
+    app: &OsmosisTestApp,
+    msg: CosmosMsg,
+    signer: &SigningAccount,
+) -> RunnerResult<()> {
+    match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds,
+        }) => app
+            .execute::<_, MsgExecuteContractResponse>(
+                MsgExecuteContract {
+                    sender: signer.address(),
+                    contract: contract_addr,
+                    msg: msg.to_vec(),
+                    funds: funds
+                        .into_iter()
+                        .map(|coin| BaseCoin {
+                            denom: coin.denom,
+                            amount: coin.amount.to_string(),
+                        })
+                        .collect(),
+                },
+                MsgExecuteContract::TYPE_URL,
+                signer,
+            )
+            .map(|_| ()),
+        msg => panic!("unsupported message: {:?}", msg),
+    }
+}
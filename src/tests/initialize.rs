@@ -1,8 +1,10 @@
 use std::str::FromStr;
 
+use apollo_cw_asset::AssetInfoBase;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Attribute, Coin, Decimal, Uint128};
 use cw_dex::osmosis::OsmosisPool;
+use osmosis_std::types::cosmos::bank::v1beta1::QueryBalanceRequest;
 use osmosis_std::types::cosmos::base::v1beta1::Coin as OsmoCoin;
 use osmosis_std::types::osmosis::concentratedliquidity::v1beta1::{
     CreateConcentratedLiquidityPoolsProposal, Pool, PoolRecord, PoolsRequest,
@@ -18,7 +20,7 @@ use osmosis_test_tube::{
     osmosis_std::types::osmosis::concentratedliquidity::{
         poolmodel::concentrated::v1beta1::MsgCreateConcentratedPool, v1beta1::MsgCreatePosition,
     },
-    Account, ConcentratedLiquidity, GovWithAppAccess, Module, OsmosisTestApp, PoolManager,
+    Account, Bank, ConcentratedLiquidity, GovWithAppAccess, Module, OsmosisTestApp, PoolManager,
     SigningAccount, TokenFactory, Wasm,
 };
 
@@ -238,6 +240,49 @@ pub fn default_init() -> (OsmosisTestApp, Addr, Vec<PoolWithDenoms>, SigningAcco
     )
 }
 
+/// Returns the denom that the third pool of [`default_init`] pairs with the
+/// first denom of the first pool, i.e. the intermediate denom of
+/// [`two_hop_path`].
+pub fn intermediate_denom(pools: &[PoolWithDenoms]) -> String {
+    if pools[2].denom0 == pools[0].denom0 {
+        pools[2].denom1.clone()
+    } else {
+        pools[2].denom0.clone()
+    }
+}
+
+/// Returns a path from the first to the second denom of the first pool of
+/// [`default_init`] that swaps through the [`intermediate_denom`] over the
+/// third and fourth pools.
+pub fn two_hop_path(pools: &[PoolWithDenoms]) -> SwapOperationsListUnchecked {
+    let intermediate_denom = intermediate_denom(pools);
+    SwapOperationsListUnchecked::new(vec![
+        SwapOperationBase::new(
+            cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pools[2].pool)),
+            AssetInfoBase::Native(pools[0].denom0.clone()),
+            AssetInfoBase::Native(intermediate_denom.clone()),
+        ),
+        SwapOperationBase::new(
+            cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pools[3].pool)),
+            AssetInfoBase::Native(intermediate_denom),
+            AssetInfoBase::Native(pools[0].denom1.clone()),
+        ),
+    ])
+}
+
+/// Returns the balance of `denom` held by `address`.
+pub fn query_balance(app: &OsmosisTestApp, address: &str, denom: &str) -> Uint128 {
+    Bank::new(app)
+        .query_balance(&QueryBalanceRequest {
+            address: address.to_string(),
+            denom: denom.to_string(),
+        })
+        .unwrap()
+        .balance
+        .map(|coin| Uint128::from_str(&coin.amount).unwrap())
+        .unwrap_or_default()
+}
+
 pub fn init_test_contract(
     app: OsmosisTestApp,
     admin: SigningAccount,
@@ -434,7 +479,10 @@ pub fn init_test_contract(
     let contract = wasm
         .instantiate(
             code_id,
-            &InstantiateMsg {},
+            &InstantiateMsg {
+                fee_bps: 0,
+                fee_recipient: admin.address(),
+            },
             Some(admin.address().as_str()),
             Some("cw-dex-router"),
             &[],
@@ -469,6 +517,8 @@ fn default_init_works() {
                             pool.denom0.clone(),
                         ),
                         ask_asset_info: apollo_cw_asset::AssetInfoBase::Native(pool.denom1).clone(),
+                        min_out: None,
+                        max_price_impact: None,
                     }])
                     .into(),
                     bidirectional: true,
@@ -491,6 +541,7 @@ fn default_init_works() {
                 ),
                 offer_amount: Uint128::from(100000000u128),
                 exclude_paths: None,
+                hop_penalty: None,
             },
         )
         .unwrap();
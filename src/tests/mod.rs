@@ -1,3 +1,13 @@
+mod basket_liquidate;
+mod best_path;
+#[cfg(feature = "astroport")]
+mod cw20;
+mod discovery;
+mod fees;
 mod helpers;
 mod initialize;
 mod multipool_paths;
+mod path_management;
+mod router_helpers;
+mod split_swap;
+mod swap_operations;
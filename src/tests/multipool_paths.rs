@@ -427,7 +427,10 @@ pub fn init_test_contract(
     let contract = wasm
         .instantiate(
             code_id,
-            &InstantiateMsg {},
+            &InstantiateMsg {
+                fee_bps: 0,
+                fee_recipient: admin.address(),
+            },
             Some(admin.address().as_str()),
             Some("cw-dex-router"),
             &[],
@@ -468,6 +471,8 @@ fn multiple_pools_work() {
                             pool.denom0.clone(),
                         ),
                         ask_asset_info: apollo_cw_asset::AssetInfoBase::Native(pool.denom1).clone(),
+                        min_out: None,
+                        max_price_impact: None,
                     }])
                     .into(),
                     bidirectional: true,
@@ -490,6 +495,7 @@ fn multiple_pools_work() {
                 ),
                 offer_amount: Uint128::from(10000u128),
                 exclude_paths: None,
+                hop_penalty: None,
             },
         )
         .unwrap();
@@ -523,7 +529,10 @@ fn multiple_pools_work() {
                 operations: resp.operations.into(),
                 offer_amount: None,
                 minimum_receive: Some(Uint128::one()),
+                max_spread: None,
+                belief_price: None,
                 to: None,
+                deadline: None,
             },
             &[Coin::new(10000u128, pools.first().unwrap().denom0.clone())],
             &admin,
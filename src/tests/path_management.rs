@@ -0,0 +1,67 @@
+use apollo_cw_asset::AssetInfoBase;
+use cw_dex::osmosis::OsmosisPool;
+use osmosis_test_tube::{Module, RunnerResult, Wasm};
+
+use crate::msg::{ExecuteMsg, QueryMsg};
+use crate::operations::{SwapOperationBase, SwapOperationsList, SwapOperationsListUnchecked};
+
+use super::initialize::default_init;
+
+#[test]
+fn remove_bidirectional_path_removes_reverse_path() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    let pool = pools.first().unwrap().clone();
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::SetPath {
+                offer_asset: AssetInfoBase::Native(pool.denom0.clone()),
+                ask_asset: AssetInfoBase::Native(pool.denom1.clone()),
+                path: SwapOperationsListUnchecked::new(vec![SwapOperationBase {
+                    pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+                    offer_asset_info: AssetInfoBase::Native(pool.denom0.clone()),
+                    ask_asset_info: AssetInfoBase::Native(pool.denom1.clone()),
+                    min_out: None,
+                    max_price_impact: None,
+                }]),
+                bidirectional: true,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::RemovePath {
+                offer_asset: AssetInfoBase::Native(pool.denom0.clone()),
+                ask_asset: AssetInfoBase::Native(pool.denom1.clone()),
+                path_id: 1,
+                bidirectional: true,
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+
+    let paths: RunnerResult<Vec<(u64, SwapOperationsList)>> = wasm.query(
+        &contract_address.to_string(),
+        &QueryMsg::PathsForPair {
+            offer_asset: AssetInfoBase::Native(pool.denom0.clone()),
+            ask_asset: AssetInfoBase::Native(pool.denom1.clone()),
+        },
+    );
+    assert!(paths.is_err());
+
+    let reverse_paths: RunnerResult<Vec<(u64, SwapOperationsList)>> = wasm.query(
+        &contract_address.to_string(),
+        &QueryMsg::PathsForPair {
+            offer_asset: AssetInfoBase::Native(pool.denom1),
+            ask_asset: AssetInfoBase::Native(pool.denom0),
+        },
+    );
+    assert!(reverse_paths.is_err());
+}
@@ -0,0 +1,441 @@
+use apollo_cw_asset::{Asset, AssetInfo, AssetList};
+use cosmwasm_std::{Addr, Coin, QuerierWrapper, Uint128};
+use cw_dex::osmosis::OsmosisPool;
+use cw_dex::Pool;
+use osmosis_test_tube::{Account, OsmosisTestApp, SigningAccount};
+
+use crate::helpers::CwDexRouter;
+use crate::msg::{AdminResponse, PauseInfoResponse};
+use crate::operations::{SwapOperation, SwapOperationsList};
+use crate::state::HopPenalty;
+
+use super::helpers::{execute_wasm_msg, TestQuerier};
+use super::initialize::{default_init, PoolWithDenoms, FEE_DENOM};
+
+fn native(denom: &str) -> AssetInfo {
+    AssetInfo::Native(denom.to_string())
+}
+
+fn one_hop_path(pool_id: u64, offer_denom: &str, ask_denom: &str) -> SwapOperationsList {
+    SwapOperationsList::new(vec![SwapOperation::new(
+        Pool::Osmosis(OsmosisPool::unchecked(pool_id)),
+        native(offer_denom),
+        native(ask_denom),
+    )])
+}
+
+/// Stores a bidirectional one hop path between the denoms of the second pool
+/// and returns it.
+fn set_path(
+    app: &OsmosisTestApp,
+    router: &CwDexRouter,
+    pools: &[PoolWithDenoms],
+    admin: &SigningAccount,
+) -> SwapOperationsList {
+    let pool = &pools[1];
+    let path = one_hop_path(pool.pool, &pool.denom0, &pool.denom1);
+    execute_wasm_msg(
+        app,
+        router
+            .set_path_msg(native(&pool.denom0), native(&pool.denom1), &path, true)
+            .unwrap(),
+        admin,
+    )
+    .unwrap();
+    path
+}
+
+#[test]
+fn path_helpers_round_trip() {
+    let (app, contract_address, pools, admin) = default_init();
+    let router = CwDexRouter::new(&contract_address);
+    let test_querier = TestQuerier(&app);
+    let querier = QuerierWrapper::new(&test_querier);
+    let (denom0, denom1) = (pools[1].denom0.clone(), pools[1].denom1.clone());
+
+    let path = set_path(&app, &router, &pools, &admin);
+    assert_eq!(
+        router
+            .query_path_for_pair(&querier, &native(&denom0), &native(&denom1))
+            .unwrap(),
+        vec![(1, path.clone())]
+    );
+    assert_eq!(
+        router.query_all_paths(&querier, None, None).unwrap().len(),
+        2
+    );
+    assert_eq!(
+        router
+            .query_supported_offer_assets(&querier, &native(&denom1), None, None)
+            .unwrap(),
+        vec![native(&denom0)]
+    );
+    assert_eq!(
+        router
+            .query_supported_ask_assets(&querier, &native(&denom0), None, None)
+            .unwrap(),
+        vec![native(&denom1)]
+    );
+
+    // Replace the path with one through the first pool, which has the same
+    // denoms
+    let new_path = one_hop_path(pools[0].pool, &denom0, &denom1);
+    execute_wasm_msg(
+        &app,
+        router
+            .replace_path_msg(native(&denom0), native(&denom1), 1, &new_path, true)
+            .unwrap(),
+        &admin,
+    )
+    .unwrap();
+    assert_eq!(
+        router
+            .query_path_for_pair(&querier, &native(&denom0), &native(&denom1))
+            .unwrap(),
+        vec![(1, new_path)]
+    );
+
+    execute_wasm_msg(
+        &app,
+        router
+            .remove_path_msg(native(&denom0), native(&denom1), 1, true)
+            .unwrap(),
+        &admin,
+    )
+    .unwrap();
+    assert!(router
+        .query_path_for_pair(&querier, &native(&denom0), &native(&denom1))
+        .is_err());
+    assert!(router
+        .query_all_paths(&querier, None, None)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn query_helpers_round_trip() {
+    let (app, contract_address, pools, admin) = default_init();
+    let router = CwDexRouter::new(&contract_address);
+    let test_querier = TestQuerier(&app);
+    let querier = QuerierWrapper::new(&test_querier);
+    let (denom0, denom1) = (pools[1].denom0.clone(), pools[1].denom1.clone());
+    let path = set_path(&app, &router, &pools, &admin);
+    let offer_amount = Uint128::from(10000u128);
+
+    let simulation = router
+        .simulate_swap_operations(&querier, offer_amount, &path)
+        .unwrap();
+    assert!(!simulation.net_amount.is_zero());
+
+    let reverse_simulation = router
+        .simulate_reverse_swap_operations(&querier, simulation.net_amount, &path)
+        .unwrap();
+    assert!(reverse_simulation.offer_amount <= offer_amount);
+
+    let split_simulation = router
+        .simulate_split_swap(&querier, &[(offer_amount, path.clone())])
+        .unwrap();
+    assert_eq!(split_simulation, simulation);
+
+    let basket_simulation = router
+        .simulate_basket_liquidate(
+            &querier,
+            AssetList::from(vec![Asset::new(native(&denom0), offer_amount)]),
+            &native(&denom1),
+        )
+        .unwrap();
+    assert_eq!(basket_simulation, simulation);
+
+    let best_path = router
+        .query_best_path_for_pair(
+            &querier,
+            &native(&denom0),
+            offer_amount,
+            &native(&denom1),
+            None,
+            Some(HopPenalty::Bps(0)),
+        )
+        .unwrap();
+    assert_eq!(best_path.operations, path);
+    assert_eq!(best_path.return_amount, simulation.gross_amount);
+
+    let path_simulations = router
+        .query_simulate_paths_for_pair(&querier, &native(&denom0), offer_amount, &native(&denom1))
+        .unwrap();
+    assert_eq!(path_simulations.len(), 1);
+    assert_eq!(
+        path_simulations[0].return_amount,
+        Some(simulation.gross_amount)
+    );
+
+    let best_split = router
+        .query_best_split_for_pair(
+            &querier,
+            &native(&denom0),
+            offer_amount,
+            &native(&denom1),
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(best_split.return_amount, simulation.gross_amount);
+
+    execute_wasm_msg(
+        &app,
+        router
+            .update_config_msg(Some(10), None, Some(HopPenalty::Bps(5)), None, None)
+            .unwrap(),
+        &admin,
+    )
+    .unwrap();
+    let config = router.query_config(&querier).unwrap();
+    assert_eq!(config.fee_bps, 10);
+    assert_eq!(config.default_hop_penalty, Some(HopPenalty::Bps(5)));
+
+    let pool = Pool::Osmosis(OsmosisPool::unchecked(pools[1].pool));
+    execute_wasm_msg(
+        &app,
+        router.register_pool_msg(pool.clone()).unwrap(),
+        &admin,
+    )
+    .unwrap();
+    let registered_pools = router.query_registered_pools(&querier, None, None).unwrap();
+    assert_eq!(registered_pools.len(), 1);
+    assert_eq!(registered_pools[0].1.pool, pool);
+
+    let discovered_paths = router
+        .query_discover_paths(
+            &querier,
+            &native(&denom0),
+            &native(&denom1),
+            1,
+            offer_amount,
+        )
+        .unwrap();
+    assert_eq!(discovered_paths.len(), 1);
+
+    execute_wasm_msg(
+        &app,
+        router.deregister_pool_msg(registered_pools[0].0).unwrap(),
+        &admin,
+    )
+    .unwrap();
+    assert!(router
+        .query_registered_pools(&querier, None, None)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn swap_helpers_round_trip() {
+    let (app, contract_address, pools, admin) = default_init();
+    let router = CwDexRouter::new(&contract_address);
+    let (denom0, denom1) = (pools[1].denom0.clone(), pools[1].denom1.clone());
+    let path = set_path(&app, &router, &pools, &admin);
+    let offer_amount = Uint128::from(10000u128);
+    let funds = vec![Coin::new(offer_amount.u128(), denom0.clone())];
+    let recipient = Some(admin.address());
+
+    let msgs = vec![
+        router
+            .execute_swap_operations_msg(
+                &path,
+                Some(offer_amount),
+                None,
+                None,
+                None,
+                recipient.clone(),
+                None,
+                funds.clone(),
+            )
+            .unwrap(),
+        router
+            .execute_swap_operations_exact_out_msg(
+                &path,
+                Uint128::from(100u128),
+                offer_amount,
+                recipient.clone(),
+                funds.clone(),
+            )
+            .unwrap(),
+        router
+            .execute_split_swap_msg(
+                &[(offer_amount, path.clone())],
+                None,
+                recipient.clone(),
+                funds,
+            )
+            .unwrap(),
+        router
+            .swap_best_path_msg(
+                &Asset::new(native(&denom0), offer_amount),
+                &native(&denom1),
+                None,
+                recipient.clone(),
+                None,
+            )
+            .unwrap(),
+    ];
+    for msg in msgs {
+        execute_wasm_msg(&app, msg, &admin).unwrap();
+    }
+
+    // Only native offer assets, so no allowance messages are returned
+    let msgs = router
+        .basket_liquidate_msgs(
+            AssetList::from(vec![Asset::new(native(&denom0), offer_amount)]),
+            &native(&denom1),
+            None,
+            recipient,
+        )
+        .unwrap();
+    assert_eq!(msgs.len(), 1);
+    for msg in msgs {
+        execute_wasm_msg(&app, msg, &admin).unwrap();
+    }
+}
+
+#[test]
+fn admin_helpers_round_trip() {
+    let (app, contract_address, pools, admin) = default_init();
+    let router = CwDexRouter::new(&contract_address);
+    let test_querier = TestQuerier(&app);
+    let querier = QuerierWrapper::new(&test_querier);
+    let accounts = app
+        .init_accounts(&[Coin::new(1_000_000_000u128, FEE_DENOM)], 2)
+        .unwrap();
+    let (new_admin, other) = (&accounts[0], &accounts[1]);
+
+    // Path managers
+    execute_wasm_msg(
+        &app,
+        router.add_path_manager_msg(other.address()).unwrap(),
+        &admin,
+    )
+    .unwrap();
+    assert_eq!(
+        router.query_path_managers(&querier, None, None).unwrap(),
+        vec![Addr::unchecked(other.address())]
+    );
+    execute_wasm_msg(
+        &app,
+        router.remove_path_manager_msg(other.address()).unwrap(),
+        &admin,
+    )
+    .unwrap();
+    assert!(router
+        .query_path_managers(&querier, None, None)
+        .unwrap()
+        .is_empty());
+
+    // Guardian and pause
+    execute_wasm_msg(
+        &app,
+        router.set_guardian_msg(Some(other.address())).unwrap(),
+        &admin,
+    )
+    .unwrap();
+    execute_wasm_msg(&app, router.pause_msg().unwrap(), other).unwrap();
+    assert_eq!(
+        router.query_pause_info(&querier).unwrap(),
+        PauseInfoResponse {
+            paused: true,
+            guardian: Some(Addr::unchecked(other.address())),
+        }
+    );
+    execute_wasm_msg(&app, router.unpause_msg().unwrap(), other).unwrap();
+    assert!(!router.query_pause_info(&querier).unwrap().paused);
+
+    // Pool blocklist
+    let pool = Pool::Osmosis(OsmosisPool::unchecked(pools[1].pool));
+    execute_wasm_msg(&app, router.block_pool_msg(pool.clone()).unwrap(), other).unwrap();
+    assert_eq!(
+        router.query_blocked_pools(&querier, None, None).unwrap(),
+        vec![pool.clone()]
+    );
+    execute_wasm_msg(&app, router.unblock_pool_msg(pool).unwrap(), other).unwrap();
+    assert!(router
+        .query_blocked_pools(&querier, None, None)
+        .unwrap()
+        .is_empty());
+
+    // Asset allowlist and denylist
+    let (denom0, denom1) = (native(&pools[1].denom0), native(&pools[1].denom1));
+    execute_wasm_msg(
+        &app,
+        router
+            .update_allowed_assets_msg(&[denom0.clone()], &[])
+            .unwrap(),
+        &admin,
+    )
+    .unwrap();
+    execute_wasm_msg(
+        &app,
+        router
+            .update_denied_assets_msg(&[denom1.clone()], &[])
+            .unwrap(),
+        &admin,
+    )
+    .unwrap();
+    assert_eq!(
+        router.query_allowed_assets(&querier, None, None).unwrap(),
+        vec![denom0.clone()]
+    );
+    assert_eq!(
+        router.query_denied_assets(&querier, None, None).unwrap(),
+        vec![denom1.clone()]
+    );
+    execute_wasm_msg(
+        &app,
+        router.update_allowed_assets_msg(&[], &[denom0]).unwrap(),
+        &admin,
+    )
+    .unwrap();
+    execute_wasm_msg(
+        &app,
+        router.update_denied_assets_msg(&[], &[denom1]).unwrap(),
+        &admin,
+    )
+    .unwrap();
+    assert!(router
+        .query_allowed_assets(&querier, None, None)
+        .unwrap()
+        .is_empty());
+    assert!(router
+        .query_denied_assets(&querier, None, None)
+        .unwrap()
+        .is_empty());
+
+    // Admin transfer
+    execute_wasm_msg(
+        &app,
+        router.propose_new_admin_msg(other.address()).unwrap(),
+        &admin,
+    )
+    .unwrap();
+    execute_wasm_msg(&app, router.clear_proposal_msg().unwrap(), &admin).unwrap();
+    execute_wasm_msg(
+        &app,
+        router.propose_new_admin_msg(new_admin.address()).unwrap(),
+        &admin,
+    )
+    .unwrap();
+    assert_eq!(
+        router.query_admin(&querier).unwrap(),
+        AdminResponse {
+            admin: Some(Addr::unchecked(admin.address())),
+            pending_admin: Some(Addr::unchecked(new_admin.address())),
+        }
+    );
+    execute_wasm_msg(&app, router.accept_admin_msg().unwrap(), new_admin).unwrap();
+    assert_eq!(
+        router.query_admin(&querier).unwrap(),
+        AdminResponse {
+            admin: Some(Addr::unchecked(new_admin.address())),
+            pending_admin: None,
+        }
+    );
+
+    execute_wasm_msg(&app, router.renounce_admin_msg().unwrap(), new_admin).unwrap();
+    assert_eq!(router.query_admin(&querier).unwrap().admin, None);
+}
@@ -0,0 +1,147 @@
+use apollo_cw_asset::AssetInfoBase;
+use cosmwasm_std::{Coin, Uint128};
+use cw_dex::osmosis::OsmosisPool;
+use osmosis_test_tube::{Account, Module, Wasm};
+
+use crate::msg::{
+    BestPathForPairResponse, BestSplitForPairResponse, ExecuteMsg, QueryMsg, SimulateSwapResponse,
+};
+use crate::operations::{SwapOperationBase, SwapOperationsListUnchecked};
+
+use super::initialize::{default_init, query_balance, two_hop_path};
+
+#[test]
+fn split_swap_over_two_pools_works() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    // the first two pools are both (denom0, denom1) pools
+    let denom0 = pools[0].denom0.clone();
+    let denom1 = pools[0].denom1.clone();
+    let routes: Vec<(Uint128, SwapOperationsListUnchecked)> = pools[..2]
+        .iter()
+        .map(|pool| {
+            (
+                Uint128::from(500u128),
+                SwapOperationsListUnchecked::new(vec![SwapOperationBase {
+                    pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+                    offer_asset_info: AssetInfoBase::Native(denom0.clone()),
+                    ask_asset_info: AssetInfoBase::Native(denom1.clone()),
+                    min_out: None,
+                    max_price_impact: None,
+                }]),
+            )
+        })
+        .collect();
+
+    let simulation: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateSplitSwap {
+                routes: routes.clone(),
+            },
+        )
+        .unwrap();
+    let expected_out = simulation.net_amount;
+    assert!(!expected_out.is_zero());
+
+    let balance_before = query_balance(&app, &admin.address(), &denom1);
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::ExecuteSplitSwap {
+                routes,
+                minimum_receive: Some(expected_out),
+                to: None,
+            },
+            &[Coin::new(1000u128, denom0.clone())],
+            &admin,
+        )
+        .unwrap();
+
+    assert_eq!(
+        query_balance(&app, &admin.address(), &denom1) - balance_before,
+        expected_out
+    );
+}
+
+#[test]
+fn best_split_for_pair_beats_best_single_path() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    // A one hop and a two hop path from denom0 to denom1
+    let denom0 = pools[0].denom0.clone();
+    let denom1 = pools[0].denom1.clone();
+    let paths = vec![
+        SwapOperationsListUnchecked::new(vec![SwapOperationBase::new(
+            cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pools[1].pool)),
+            AssetInfoBase::Native(denom0.clone()),
+            AssetInfoBase::Native(denom1.clone()),
+        )]),
+        two_hop_path(&pools),
+    ];
+    for path in paths {
+        let _ = wasm
+            .execute(
+                &contract_address.to_string(),
+                &ExecuteMsg::SetPath {
+                    offer_asset: AssetInfoBase::Native(denom0.clone()),
+                    ask_asset: AssetInfoBase::Native(denom1.clone()),
+                    path,
+                    bidirectional: false,
+                },
+                &[],
+                &admin,
+            )
+            .unwrap();
+    }
+
+    let offer_amount = Uint128::from(1_000_000u128);
+    let best_split: BestSplitForPairResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::BestSplitForPair {
+                offer_asset: AssetInfoBase::Native(denom0.clone()),
+                offer_amount,
+                ask_asset: AssetInfoBase::Native(denom1.clone()),
+                max_splits: None,
+                granularity: None,
+            },
+        )
+        .unwrap();
+    let best_path: BestPathForPairResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::BestPathForPair {
+                offer_asset: AssetInfoBase::Native(denom0.clone()),
+                offer_amount,
+                ask_asset: AssetInfoBase::Native(denom1),
+                exclude_paths: None,
+                hop_penalty: None,
+            },
+        )
+        .unwrap();
+
+    // The whole offer amount is split, and splitting returns at least as much
+    // as swapping everything through the best path
+    let split_amounts: Uint128 = best_split.routes.iter().map(|(amount, _)| amount).sum();
+    assert_eq!(split_amounts, offer_amount);
+    assert!(best_split.return_amount >= best_path.return_amount);
+
+    // The split return is what the routes simulate to
+    let routes: Vec<(Uint128, SwapOperationsListUnchecked)> = best_split
+        .routes
+        .into_iter()
+        .map(|(amount, operations)| (amount, operations.into()))
+        .collect();
+    let simulation: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateSplitSwap { routes },
+        )
+        .unwrap();
+    assert_eq!(simulation.gross_amount, best_split.return_amount);
+    assert_eq!(simulation.net_amount, best_split.net_amount);
+}
@@ -0,0 +1,411 @@
+use apollo_cw_asset::AssetInfoBase;
+use cosmwasm_std::{Coin, Decimal, Uint128};
+use cw_dex::osmosis::OsmosisPool;
+use osmosis_std::types::cosmos::bank::v1beta1::MsgSend;
+use osmosis_std::types::cosmos::base::v1beta1::Coin as BaseCoin;
+use osmosis_test_tube::{Account, Bank, Module, Wasm};
+
+use crate::msg::{ExecuteMsg, QueryMsg, SimulateReverseSwapResponse, SimulateSwapResponse};
+use crate::operations::{SwapOperationBase, SwapOperationsListUnchecked};
+
+use super::initialize::{default_init, intermediate_denom, query_balance, two_hop_path};
+
+#[test]
+fn offer_amount_must_match_native_funds() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    let pool = pools.first().unwrap().clone();
+    let operations = SwapOperationsListUnchecked::new(vec![SwapOperationBase {
+        pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+        offer_asset_info: AssetInfoBase::Native(pool.denom0.clone()),
+        ask_asset_info: AssetInfoBase::Native(pool.denom1.clone()),
+        min_out: None,
+        max_price_impact: None,
+    }]);
+    let offer_amount = Uint128::from(10000u128);
+
+    let res = wasm.execute(
+        &contract_address.to_string(),
+        &ExecuteMsg::ExecuteSwapOperations {
+            operations: operations.clone(),
+            offer_amount: Some(offer_amount),
+            minimum_receive: None,
+            max_spread: None,
+            belief_price: None,
+            to: None,
+            deadline: None,
+        },
+        &[Coin::new(offer_amount.u128() + 1, pool.denom0.clone())],
+        &admin,
+    );
+    assert!(res.is_err());
+
+    let simulation: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations,
+                offer_amount: Some(offer_amount),
+                minimum_receive: Some(simulation.net_amount),
+                max_spread: None,
+                belief_price: None,
+                to: None,
+                deadline: None,
+            },
+            &[Coin::new(offer_amount.u128(), pool.denom0.clone())],
+            &admin,
+        )
+        .unwrap();
+}
+
+#[test]
+fn exact_out_swap_refunds_unspent_offer() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    // Swap denom0 -> denom2 -> denom1 over the last two pools to also reverse
+    // simulate the intermediate hop
+    let offer_denom = pools[0].denom0.clone();
+    let ask_denom = pools[0].denom1.clone();
+    let operations = two_hop_path(&pools);
+    let ask_amount = Uint128::from(10000u128);
+
+    let simulation: SimulateReverseSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateReverseSwapOperations {
+                ask_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+
+    // The simulated offer amount returns at least the ask amount
+    let forward: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount: simulation.offer_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+    assert!(forward.net_amount >= ask_amount);
+
+    let offer_balance_before = query_balance(&app, &admin.address(), &offer_denom);
+    let ask_balance_before = query_balance(&app, &admin.address(), &ask_denom);
+
+    let max_offer = simulation.offer_amount + Uint128::from(5000u128);
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::ExecuteSwapOperationsExactOut {
+                operations,
+                ask_amount,
+                max_offer,
+                to: None,
+            },
+            &[Coin::new(max_offer.u128(), offer_denom.clone())],
+            &admin,
+        )
+        .unwrap();
+
+    assert_eq!(
+        offer_balance_before - query_balance(&app, &admin.address(), &offer_denom),
+        simulation.offer_amount
+    );
+    assert!(query_balance(&app, &admin.address(), &ask_denom) - ask_balance_before >= ask_amount);
+}
+
+#[test]
+fn max_spread_is_enforced_against_belief_price() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    let pool = pools.first().unwrap().clone();
+    let operations = SwapOperationsListUnchecked::new(vec![SwapOperationBase {
+        pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+        offer_asset_info: AssetInfoBase::Native(pool.denom0.clone()),
+        ask_asset_info: AssetInfoBase::Native(pool.denom1.clone()),
+        min_out: None,
+        max_price_impact: None,
+    }]);
+    let offer_amount = Uint128::from(10000u128);
+    let funds = [Coin::new(offer_amount.u128(), pool.denom0.clone())];
+
+    let simulation: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+    let swap = |max_spread: Option<Decimal>, belief_price: Option<Decimal>| {
+        wasm.execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations: operations.clone(),
+                offer_amount: None,
+                minimum_receive: None,
+                max_spread,
+                belief_price,
+                to: None,
+                deadline: None,
+            },
+            &funds,
+            &admin,
+        )
+    };
+
+    // The price at which the simulated amount is received
+    let belief_price = Decimal::from_ratio(offer_amount, simulation.net_amount);
+
+    let err = swap(Some(Decimal::percent(101)), Some(belief_price)).unwrap_err();
+    assert!(err.to_string().contains("Invalid max spread"));
+
+    let err = swap(Some(Decimal::percent(1)), None).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("max_spread and belief_price must be given together"));
+
+    // Expecting 10% more than the pool returns is more than the max spread
+    let optimistic_price = belief_price * Decimal::percent(90);
+    let err = swap(Some(Decimal::percent(1)), Some(optimistic_price)).unwrap_err();
+    assert!(err.to_string().contains("Did not receive minimum amount"));
+
+    let _ = swap(Some(Decimal::percent(1)), Some(belief_price)).unwrap();
+}
+
+#[test]
+fn operation_max_price_impact_is_enforced_per_hop() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    // A balancer pool with 1_000_000 of each denom
+    let pool = pools[1].clone();
+    let swap = |offer_amount: u128| {
+        wasm.execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations: SwapOperationsListUnchecked::new(vec![SwapOperationBase {
+                    pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+                    offer_asset_info: AssetInfoBase::Native(pool.denom0.clone()),
+                    ask_asset_info: AssetInfoBase::Native(pool.denom1.clone()),
+                    min_out: None,
+                    max_price_impact: Some(Decimal::percent(5)),
+                }]),
+                offer_amount: None,
+                minimum_receive: None,
+                max_spread: None,
+                belief_price: None,
+                to: None,
+                deadline: None,
+            },
+            &[Coin::new(offer_amount, pool.denom0.clone())],
+            &admin,
+        )
+    };
+
+    // Swapping half the pool depth moves the price by far more than 5%
+    let err = swap(500_000).unwrap_err();
+    assert!(err.to_string().contains("exceeds the maximum"));
+
+    // A small swap barely moves the price
+    let _ = swap(10_000).unwrap();
+
+    // The reference swap of a tiny amount returns nothing, so the price impact
+    // can not be measured and the swap is rejected instead of let through
+    let err = swap(100).unwrap_err();
+    assert!(err.to_string().contains("can not be measured"));
+}
+
+#[test]
+fn operation_min_out_is_enforced_per_hop() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    let pool = pools.first().unwrap().clone();
+    let operation = |min_out: Option<Uint128>| SwapOperationBase {
+        pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+        offer_asset_info: AssetInfoBase::Native(pool.denom0.clone()),
+        ask_asset_info: AssetInfoBase::Native(pool.denom1.clone()),
+        min_out,
+        max_price_impact: None,
+    };
+    let offer_amount = Uint128::from(10000u128);
+
+    let simulation: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: SwapOperationsListUnchecked::new(vec![operation(None)]),
+            },
+        )
+        .unwrap();
+
+    let err = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations: SwapOperationsListUnchecked::new(vec![operation(Some(
+                    simulation.gross_amount + Uint128::one(),
+                ))]),
+                offer_amount: None,
+                minimum_receive: None,
+                max_spread: None,
+                belief_price: None,
+                to: None,
+                deadline: None,
+            },
+            &[Coin::new(offer_amount.u128(), pool.denom0.clone())],
+            &admin,
+        )
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("did not return its minimum amount"));
+}
+
+#[test]
+fn multi_hop_swap_uses_exact_hop_amounts() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+    let bank = Bank::new(&app);
+
+    let offer_denom = pools[0].denom0.clone();
+    let ask_denom = pools[0].denom1.clone();
+    let intermediate_denom = intermediate_denom(&pools);
+    let operations = two_hop_path(&pools);
+    let offer_amount = Uint128::from(10000u128);
+
+    // Funds held by the router must not be swept into the swap
+    let held = Uint128::from(5000u128);
+    bank.send(
+        MsgSend {
+            from_address: admin.address(),
+            to_address: contract_address.to_string(),
+            amount: [&intermediate_denom, &ask_denom]
+                .iter()
+                .map(|denom| BaseCoin {
+                    denom: denom.to_string(),
+                    amount: held.to_string(),
+                })
+                .collect(),
+        },
+        &admin,
+    )
+    .unwrap();
+
+    let simulation: SimulateSwapResponse = wasm
+        .query(
+            &contract_address.to_string(),
+            &QueryMsg::SimulateSwapOperations {
+                offer_amount,
+                operations: operations.clone(),
+            },
+        )
+        .unwrap();
+
+    let ask_balance_before = query_balance(&app, &admin.address(), &ask_denom);
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::ExecuteSwapOperations {
+                operations,
+                offer_amount: Some(offer_amount),
+                minimum_receive: None,
+                max_spread: None,
+                belief_price: None,
+                to: None,
+                deadline: None,
+            },
+            &[Coin::new(offer_amount.u128(), offer_denom.clone())],
+            &admin,
+        )
+        .unwrap();
+
+    assert_eq!(
+        query_balance(&app, &admin.address(), &ask_denom) - ask_balance_before,
+        simulation.net_amount
+    );
+    assert_eq!(
+        query_balance(&app, contract_address.as_str(), &intermediate_denom),
+        held
+    );
+    assert_eq!(
+        query_balance(&app, contract_address.as_str(), &ask_denom),
+        held
+    );
+}
+
+#[test]
+fn denied_asset_cannot_be_swapped() {
+    let (app, contract_address, pools, admin) = default_init();
+    let wasm = Wasm::new(&app);
+
+    let pool = pools.first().unwrap().clone();
+    let operations = SwapOperationsListUnchecked::new(vec![SwapOperationBase {
+        pool: cw_dex::Pool::Osmosis(OsmosisPool::unchecked(pool.pool)),
+        offer_asset_info: AssetInfoBase::Native(pool.denom0.clone()),
+        ask_asset_info: AssetInfoBase::Native(pool.denom1.clone()),
+        min_out: None,
+        max_price_impact: None,
+    }]);
+    let offer_amount = Uint128::from(10000u128);
+    let swap_msg = ExecuteMsg::ExecuteSwapOperations {
+        operations,
+        offer_amount: Some(offer_amount),
+        minimum_receive: None,
+        max_spread: None,
+        belief_price: None,
+        to: None,
+        deadline: None,
+    };
+    let funds = [Coin::new(offer_amount.u128(), pool.denom0.clone())];
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::UpdateDeniedAssets {
+                add: vec![AssetInfoBase::Native(pool.denom1.clone())],
+                remove: vec![],
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+    let err = wasm
+        .execute(&contract_address.to_string(), &swap_msg, &funds, &admin)
+        .unwrap_err();
+    assert!(err.to_string().contains("is not allowed to be routed"));
+
+    let _ = wasm
+        .execute(
+            &contract_address.to_string(),
+            &ExecuteMsg::UpdateDeniedAssets {
+                add: vec![],
+                remove: vec![AssetInfoBase::Native(pool.denom1.clone())],
+            },
+            &[],
+            &admin,
+        )
+        .unwrap();
+    let _ = wasm
+        .execute(&contract_address.to_string(), &swap_msg, &funds, &admin)
+        .unwrap();
+}
@@ -0,0 +1,220 @@
+//! A mock Astroport pair between a Cw20 token and a native denom. It answers
+//! the pair messages and queries used by `cw-dex` and swaps at a fixed rate of
+//! 1:1 without fees, paying out of its own balance. Fields of the Astroport
+//! messages that the mock does not need are ignored.
+
+use cosmwasm_std::{
+    entry_point, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut,
+    Env, MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Item;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct InstantiateMsg {
+    pub token: String,
+    pub denom: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Config {
+    token: Addr,
+    denom: String,
+}
+
+const CONFIG: Item<Config> = Item::new("config");
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    Swap {
+        offer_asset: Asset,
+        to: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    Swap { to: Option<String> },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Pair {},
+    Pool {},
+    Simulation { offer_asset: Asset },
+    ReverseSimulation { ask_asset: Asset },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetInfo {
+    Token { contract_addr: Addr },
+    NativeToken { denom: String },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Asset {
+    pub info: AssetInfo,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PairType {
+    Xyk {},
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PairInfo {
+    pub asset_infos: Vec<AssetInfo>,
+    pub contract_addr: Addr,
+    pub liquidity_token: Addr,
+    pub pair_type: PairType,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PoolResponse {
+    pub assets: Vec<Asset>,
+    pub total_share: Uint128,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SimulationResponse {
+    pub return_amount: Uint128,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReverseSimulationResponse {
+    pub offer_amount: Uint128,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+}
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            token: deps.api.addr_validate(&msg.token)?,
+            denom: msg.denom,
+        },
+    )?;
+    Ok(Response::default())
+}
+
+#[entry_point]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    match msg {
+        // Swap the received token for the native denom
+        ExecuteMsg::Receive(cw20_msg) => {
+            if info.sender != config.token {
+                return Err(StdError::generic_err("unknown token"));
+            }
+            let Cw20HookMsg::Swap { to } = from_json(&cw20_msg.msg)?;
+            let msg = BankMsg::Send {
+                to_address: to.unwrap_or(cw20_msg.sender),
+                amount: vec![Coin::new(cw20_msg.amount.u128(), config.denom)],
+            };
+            Ok(Response::new().add_message(msg))
+        }
+        // Swap the sent native denom for the token
+        ExecuteMsg::Swap { offer_asset, to } => {
+            let sent = info
+                .funds
+                .iter()
+                .find(|coin| coin.denom == config.denom)
+                .map(|coin| coin.amount)
+                .unwrap_or_default();
+            if sent != offer_asset.amount {
+                return Err(StdError::generic_err("offer amount was not sent"));
+            }
+            let msg: CosmosMsg = WasmMsg::Execute {
+                contract_addr: config.token.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: to.unwrap_or_else(|| info.sender.to_string()),
+                    amount: sent,
+                })?,
+                funds: vec![],
+            }
+            .into();
+            Ok(Response::new().add_message(msg))
+        }
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let config = CONFIG.load(deps.storage)?;
+    match msg {
+        QueryMsg::Pair {} => to_json_binary(&PairInfo {
+            asset_infos: asset_infos(&config).into(),
+            contract_addr: env.contract.address.clone(),
+            liquidity_token: env.contract.address,
+            pair_type: PairType::Xyk {},
+        }),
+        QueryMsg::Pool {} => {
+            let token_balance: BalanceResponse = deps.querier.query_wasm_smart(
+                &config.token,
+                &Cw20QueryMsg::Balance {
+                    address: env.contract.address.to_string(),
+                },
+            )?;
+            let native_balance = deps
+                .querier
+                .query_balance(&env.contract.address, &config.denom)?;
+            let [token, native] = asset_infos(&config);
+            to_json_binary(&PoolResponse {
+                assets: vec![
+                    Asset {
+                        info: token,
+                        amount: token_balance.balance,
+                    },
+                    Asset {
+                        info: native,
+                        amount: native_balance.amount,
+                    },
+                ],
+                total_share: Uint128::zero(),
+            })
+        }
+        QueryMsg::Simulation { offer_asset } => to_json_binary(&SimulationResponse {
+            return_amount: offer_asset.amount,
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::zero(),
+        }),
+        QueryMsg::ReverseSimulation { ask_asset } => to_json_binary(&ReverseSimulationResponse {
+            offer_amount: ask_asset.amount,
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::zero(),
+        }),
+    }
+}
+
+fn asset_infos(config: &Config) -> [AssetInfo; 2] {
+    [
+        AssetInfo::Token {
+            contract_addr: config.token.clone(),
+        },
+        AssetInfo::NativeToken {
+            denom: config.denom.clone(),
+        },
+    ]
+}
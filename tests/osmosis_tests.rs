@@ -70,7 +70,10 @@ mod osmosis_tests {
         let contract_addr = wasm
             .instantiate(
                 code_id,
-                &InstantiateMsg {},
+                &InstantiateMsg {
+                    fee_bps: 0,
+                    fee_recipient: signer.address(),
+                },
                 None,
                 Some("cw-dex-router"),
                 &[],
@@ -154,6 +157,8 @@ mod osmosis_tests {
                     pool: Pool::Osmosis(OsmosisPool::unchecked(pool_id.to_owned())),
                     offer_asset_info: AssetInfo::Native(from.to_string()),
                     ask_asset_info: AssetInfo::Native(to.to_string()),
+                    min_out: None,
+                    max_price_impact: None,
                 })
                 .collect(),
         )
@@ -583,11 +588,11 @@ mod osmosis_tests {
         // Query supported offer assets
         let querier = QuerierWrapper::new(&app);
         let supported_offer_assets =
-            cw_dex_router.query_supported_offer_assets(&querier, &ask_asset)?;
+            cw_dex_router.query_supported_offer_assets(&querier, &ask_asset, None, None)?;
 
         // Query supported ask assets
         let supported_ask_assets =
-            cw_dex_router.query_supported_ask_assets(&querier, &offer_asset)?;
+            cw_dex_router.query_supported_ask_assets(&querier, &offer_asset, None, None)?;
 
         println!("expected_offer_assets: {:?}", expected_offer_assets);
         println!("supported_offer_assets: {:?}", supported_offer_assets);